@@ -0,0 +1,359 @@
+use crate::auth::Authorized;
+use crate::openapi::ApiDoc;
+use crate::utils::ResponseAPI;
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Either, Responder, web};
+use game_primitives::admin::{AdminCommand, LogFilter, MAX_WORKERS, MinerStatus, PeerInfo, Reply};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Longest ban the operator can set, thirty days
+pub const MAX_BAN_DURATION: u64 = 30 * 24 * 60 * 60;
+
+/// API Endpoints for the node operators
+///
+/// Commands are answered by the world runner, between two loop iterations
+pub struct Admin;
+
+/// Admin channel of the world
+pub type AdminSender = web::ThinData<mpsc::Sender<AdminCommand>>;
+
+/// Log filter hook of the node, if any
+pub type LogFilterHook = web::ThinData<Option<LogFilter>>;
+
+/// Miner request body
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MinerRequest {
+    /// Starts or stops the miner
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Number of nonces mined per runner loop, from 1 to 1024
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workers: Option<usize>,
+}
+
+/// Ban query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BanQuery {
+    /// Ban duration in seconds, one hour by default and thirty days at most
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+}
+
+/// Dial request body
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DialRequest {
+    /// Multiaddr of the peer, e.g. `/ip4/1.2.3.4/udp/7331/quic-v1`
+    pub address: String,
+}
+
+/// Log filter request body
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LogRequest {
+    /// Filter directives, e.g. `info,game_network=debug`
+    pub filter: String,
+}
+
+/// Sends a command to the runner and responds with its reply
+///
+/// Rejected commands respond with the given status code
+async fn request<T>(
+    admin: &AdminSender,
+    rejected: StatusCode,
+    command: impl FnOnce(Reply<T>) -> AdminCommand,
+) -> Either<ResponseAPI<T>, ResponseAPI<String>> {
+    let (reply, response) = oneshot::channel();
+    if admin.send(command(reply)).await.is_err() {
+        return Either::Right(shutting_down());
+    }
+
+    match response.await {
+        Ok(Ok(data)) => Either::Left(ResponseAPI::success(data)),
+        Ok(Err(e)) => Either::Right(ResponseAPI::error(e).with_code(rejected)),
+        Err(_) => Either::Right(shutting_down()),
+    }
+}
+
+/// Responds to commands sent once the runner stopped
+fn shutting_down() -> ResponseAPI<String> {
+    ResponseAPI::error("World is shutting down".to_string())
+        .with_code(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+impl Admin {
+    /// Responds with the settings of the local miner
+    pub async fn miner(_: Authorized, admin: AdminSender) -> impl Responder {
+        request(&admin, StatusCode::BAD_REQUEST, AdminCommand::Miner).await
+    }
+
+    /// Starts or stops the local miner and sets the nonces it mines per loop
+    ///
+    /// The whole request is validated before any setting is applied
+    pub async fn configure_miner(
+        _: Authorized,
+        admin: AdminSender,
+        body: web::Json<MinerRequest>,
+    ) -> impl Responder {
+        let MinerRequest { enabled, workers } = body.into_inner();
+        if workers.is_some_and(|workers| !(1..=MAX_WORKERS).contains(&workers)) {
+            return Either::Right(
+                ResponseAPI::error(format!("Workers must be between 1 and {MAX_WORKERS}"))
+                    .with_code(StatusCode::BAD_REQUEST),
+            );
+        }
+
+        if let Some(enabled) = enabled
+            && let Either::Right(rejected) = request(&admin, StatusCode::BAD_REQUEST, |reply| {
+                AdminCommand::Mining(enabled, reply)
+            })
+            .await
+        {
+            return Either::Right(rejected);
+        }
+
+        match workers {
+            Some(workers) => {
+                request(&admin, StatusCode::BAD_REQUEST, |reply| {
+                    AdminCommand::Workers(workers, reply)
+                })
+                .await
+            }
+            None => request(&admin, StatusCode::BAD_REQUEST, AdminCommand::Miner).await,
+        }
+    }
+
+    /// Submits a claim of a full batch of pending shares right away
+    ///
+    /// The contract only takes batches of ten, so fewer shares are a conflict
+    pub async fn claim(_: Authorized, admin: AdminSender) -> impl Responder {
+        request(&admin, StatusCode::CONFLICT, AdminCommand::Claim).await
+    }
+
+    /// Responds with the connected peers
+    pub async fn peers(_: Authorized, admin: AdminSender) -> impl Responder {
+        request(&admin, StatusCode::SERVICE_UNAVAILABLE, AdminCommand::Peers).await
+    }
+
+    /// Bans a peer and closes its connections
+    pub async fn ban_peer(
+        _: Authorized,
+        admin: AdminSender,
+        peer_id: web::Path<String>,
+        query: web::Query<BanQuery>,
+    ) -> impl Responder {
+        if query
+            .duration
+            .is_some_and(|duration| duration > MAX_BAN_DURATION)
+        {
+            return Either::Right(
+                ResponseAPI::error(format!("Ban duration exceeds {MAX_BAN_DURATION} seconds"))
+                    .with_code(StatusCode::BAD_REQUEST),
+            );
+        }
+
+        let duration = query.duration.map(Duration::from_secs);
+        request(&admin, StatusCode::BAD_REQUEST, |reply| {
+            AdminCommand::Ban(peer_id.into_inner(), duration, reply)
+        })
+        .await
+    }
+
+    /// Dials a peer by its multiaddr
+    pub async fn dial(
+        _: Authorized,
+        admin: AdminSender,
+        body: web::Json<DialRequest>,
+    ) -> impl Responder {
+        request(&admin, StatusCode::BAD_REQUEST, |reply| {
+            AdminCommand::Dial(body.into_inner().address, reply)
+        })
+        .await
+    }
+
+    /// Replaces the log filter directives
+    pub async fn log_filter(
+        _: Authorized,
+        hook: LogFilterHook,
+        body: web::Json<LogRequest>,
+    ) -> impl Responder {
+        let Some(filter) = &hook.0 else {
+            return ResponseAPI::error("Log filter is not reloadable".to_string())
+                .with_code(StatusCode::NOT_IMPLEMENTED);
+        };
+
+        match filter.reload(&body.filter) {
+            Ok(()) => ResponseAPI::success(format!("Log filter set to {}", body.filter)),
+            Err(e) => ResponseAPI::error(e).with_code(StatusCode::BAD_REQUEST),
+        }
+    }
+
+    /// Responds with a snapshot of the world state
+    pub async fn snapshot<W: Serialize>(_: Authorized, data: web::ThinData<W>) -> impl Responder {
+        match serde_json::to_value(&data.0) {
+            Ok(snapshot) => Either::Left(ResponseAPI::success(snapshot)),
+            Err(e) => Either::Right(ResponseAPI::error(e.to_string())),
+        }
+    }
+
+    /// Describes the admin endpoints
+    pub fn document(doc: &mut ApiDoc) {
+        doc.endpoint(Method::GET, "/admin/miner", "Settings of the local miner")
+            .response::<MinerStatus>(StatusCode::OK, "The miner settings")
+            .bearer();
+        doc.endpoint(Method::POST, "/admin/miner", "Configures the local miner")
+            .body::<MinerRequest>()
+            .response::<MinerStatus>(StatusCode::OK, "The miner settings")
+            .error(
+                StatusCode::BAD_REQUEST,
+                "Invalid settings or mining disabled",
+            )
+            .bearer();
+        doc.endpoint(
+            Method::POST,
+            "/admin/claim",
+            "Submits a claim of a batch of ten pending shares",
+        )
+        .response::<usize>(StatusCode::OK, "Number of shares claimed")
+        .error(
+            StatusCode::CONFLICT,
+            "Fewer than ten shares pending, the contract takes full batches only",
+        )
+        .bearer();
+        doc.endpoint(Method::GET, "/admin/peers", "Connected peers")
+            .response::<Vec<PeerInfo>>(StatusCode::OK, "The peers and their scores")
+            .bearer();
+        doc.endpoint(Method::POST, "/admin/peers/{peer_id}/ban", "Bans a peer")
+            .query::<BanQuery>()
+            .response::<()>(StatusCode::OK, "Peer banned")
+            .error(StatusCode::BAD_REQUEST, "Invalid peer id or duration")
+            .bearer();
+        doc.endpoint(Method::POST, "/admin/dial", "Dials a peer")
+            .body::<DialRequest>()
+            .response::<()>(StatusCode::OK, "Dial started")
+            .error(StatusCode::BAD_REQUEST, "Invalid multiaddr")
+            .bearer();
+        doc.endpoint(Method::PUT, "/admin/log", "Replaces the log filter")
+            .body::<LogRequest>()
+            .response::<String>(StatusCode::OK, "Log filter replaced")
+            .error(StatusCode::BAD_REQUEST, "Invalid directives")
+            .error(StatusCode::NOT_IMPLEMENTED, "Log filter not reloadable")
+            .bearer();
+        doc.endpoint(
+            Method::GET,
+            "/admin/snapshot",
+            "Snapshot of the world state",
+        )
+        .response::<serde_json::Value>(StatusCode::OK, "The world state")
+        .bearer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use crate::utils;
+    use actix_web::{App, test};
+
+    #[actix_web::test]
+    async fn test_ban_peer() {
+        let (sender, mut commands) = mpsc::channel(1);
+        let app = test::init_service(
+            App::new()
+                .app_data(mock::token())
+                .app_data(web::ThinData(sender))
+                .app_data(web::QueryConfig::default().error_handler(utils::bad_request))
+                .route("/ban/{peer_id}", web::post().to(Admin::ban_peer)),
+        )
+        .await;
+
+        // Answer the commands like the world runner
+        actix_web::rt::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                if let AdminCommand::Ban(peer, duration, reply) = command {
+                    let banned = match peer.as_str() {
+                        "peer" => Ok(()),
+                        _ => Err(format!("Invalid peer id {peer}")),
+                    };
+                    assert_eq!(duration, Some(Duration::from_secs(60)));
+                    let _ = reply.send(banned);
+                }
+            }
+        });
+
+        let request = test::TestRequest::post()
+            .uri("/ban/peer?duration=60")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = test::TestRequest::post()
+            .uri("/ban/peer?duration=60")
+            .insert_header(mock::bearer())
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Rejected commands and malformed or overlong durations
+        for uri in [
+            "/ban/nobody?duration=60".to_string(),
+            "/ban/peer?duration=soon".to_string(),
+            format!("/ban/peer?duration={}", MAX_BAN_DURATION + 1),
+        ] {
+            let request = test::TestRequest::post()
+                .uri(&uri)
+                .insert_header(mock::bearer())
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_configure_miner() {
+        let (sender, mut commands) = mpsc::channel::<AdminCommand>(1);
+        let app = test::init_service(
+            App::new()
+                .app_data(mock::token())
+                .app_data(web::ThinData(sender))
+                .route("/miner", web::post().to(Admin::configure_miner)),
+        )
+        .await;
+
+        // Invalid requests leave the miner untouched
+        let body = MinerRequest {
+            enabled: Some(false),
+            workers: Some(MAX_WORKERS + 1),
+        };
+        let request = test::TestRequest::post()
+            .uri("/miner")
+            .insert_header(mock::bearer())
+            .set_json(&body)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(commands.try_recv().is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_runner_stopped() {
+        let (sender, commands) = mpsc::channel::<AdminCommand>(1);
+        drop(commands);
+        let app = test::init_service(
+            App::new()
+                .app_data(mock::token())
+                .app_data(web::ThinData(sender))
+                .route("/claim", web::post().to(Admin::claim)),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/claim")
+            .insert_header(mock::bearer())
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
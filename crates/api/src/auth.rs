@@ -0,0 +1,128 @@
+use crate::utils::ResponseAPI;
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{AUTHORIZATION, HeaderValue, WWW_AUTHENTICATE};
+use actix_web::{FromRequest, HttpRequest, Responder};
+use std::future::{Ready, ready};
+use std::sync::Arc;
+
+/// Api Token
+///
+/// Bearer token expected by the write endpoints
+#[derive(Debug, Clone)]
+pub struct ApiToken(Option<Arc<str>>);
+
+impl ApiToken {
+    /// Creates a new token, rejecting every request when unset
+    pub fn new(token: Option<String>) -> Self {
+        Self(token.map(Into::into))
+    }
+
+    /// Checks a presented token in constant time
+    fn verify(&self, presented: &str) -> bool {
+        let Some(token) = &self.0 else {
+            return false;
+        };
+
+        token.len() == presented.len()
+            && token
+                .bytes()
+                .zip(presented.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// Authorized
+///
+/// Extracted from requests carrying the bearer token
+#[derive(Debug)]
+pub struct Authorized;
+
+impl FromRequest for Authorized {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let presented = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "));
+
+        let authorized = match (req.app_data::<ApiToken>(), presented) {
+            (Some(token), Some(presented)) => token.verify(presented.trim()),
+            _ => false,
+        };
+
+        ready(match authorized {
+            true => Ok(Authorized),
+            false => Err(unauthorized(req)),
+        })
+    }
+}
+
+/// Responds to requests without a valid token
+fn unauthorized(req: &HttpRequest) -> actix_web::Error {
+    let mut response = ResponseAPI::error("Unauthorized")
+        .with_code(StatusCode::UNAUTHORIZED)
+        .respond_to(req);
+    response
+        .headers_mut()
+        .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    actix_web::error::InternalError::from_response("Unauthorized", response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use actix_web::{App, test, web};
+
+    async fn guarded(_: Authorized) -> &'static str {
+        "ok"
+    }
+
+    #[actix_web::test]
+    async fn test_bearer_token() {
+        let app = test::init_service(
+            App::new()
+                .app_data(mock::token())
+                .route("/", web::post().to(guarded)),
+        )
+        .await;
+
+        // Missing or wrong tokens are challenged
+        let response = test::call_service(&app, test::TestRequest::post().to_request()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+        let request = test::TestRequest::post()
+            .insert_header(("Authorization", "Bearer wrong"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = test::TestRequest::post()
+            .insert_header(mock::bearer())
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_unset_token() {
+        let app = test::init_service(
+            App::new()
+                .app_data(ApiToken::new(None))
+                .route("/", web::post().to(guarded)),
+        )
+        .await;
+
+        // Nothing is authorized without a token
+        let request = test::TestRequest::post()
+            .insert_header(("Authorization", "Bearer "))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
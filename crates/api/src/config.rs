@@ -0,0 +1,128 @@
+use anyhow::{Result, anyhow};
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Default bind address of the API server
+const API_ADDR: &str = "0.0.0.0:8080";
+/// File holding the generated bearer token of the write endpoints
+pub const API_TOKEN_PATH: &str = "./api.token";
+
+/// Api Config
+///
+/// Used to configure the bind address, TLS and authentication of the API server
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    /// Address to bind to
+    pub bind: String,
+    /// Serves HTTPS when set
+    pub tls: Option<TlsConfig>,
+    /// Bearer token of the write endpoints, disabled when unset
+    pub token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: API_ADDR.to_string(),
+            tls: None,
+            token: None,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Loads the config from the environment
+    ///
+    /// `API_ADDR` overrides the bind address
+    /// `API_TLS_CERT` and `API_TLS_KEY` enable TLS with PEM files
+    /// `API_TOKEN` sets the bearer token, otherwise one is kept in `API_TOKEN_PATH`
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+
+        if let Ok(bind) = std::env::var("API_ADDR") {
+            config.bind = bind;
+        }
+
+        config.tls = match (std::env::var("API_TLS_CERT"), std::env::var("API_TLS_KEY")) {
+            (Ok(cert), Ok(key)) => Some(TlsConfig {
+                cert: cert.into(),
+                key: key.into(),
+            }),
+            (Err(_), Err(_)) => None,
+            _ => return Err(anyhow!("API_TLS_CERT and API_TLS_KEY must be set together")),
+        };
+
+        config.token = Some(match std::env::var("API_TOKEN") {
+            Ok(token) if !token.is_empty() => token,
+            _ => load_token(Path::new(API_TOKEN_PATH))?,
+        });
+
+        Ok(config)
+    }
+}
+
+/// Loads the bearer token from a file
+///
+/// Generates and saves a new token, readable by the owner only, when missing
+fn load_token(path: &Path) -> Result<String> {
+    if let Ok(token) = std::fs::read_to_string(path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+
+    let token = rand::random::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))?;
+    file.write_all(token.as_bytes())?;
+
+    Ok(token)
+}
+
+/// Tls Config
+///
+/// Certificate chain and private key of the API server
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM encoded certificate chain
+    pub cert: PathBuf,
+    /// PEM encoded PKCS#8 private key
+    pub key: PathBuf,
+}
+
+impl TlsConfig {
+    /// Loads the rustls server config
+    pub fn server_config(&self) -> Result<rustls::ServerConfig> {
+        let certs =
+            rustls_pemfile::certs(&mut reader(&self.cert)?).collect::<Result<Vec<_>, _>>()?;
+        let key = rustls_pemfile::pkcs8_private_keys(&mut reader(&self.key)?)
+            .next()
+            .ok_or_else(|| anyhow!("No PKCS#8 private key in {}", self.key.display()))??;
+
+        // Pick the provider, more than one is linked in the dependency tree
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(certs, key.into())?;
+
+        Ok(config)
+    }
+}
+
+/// Opens a PEM file
+fn reader(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open {}: {e}", path.display()))?;
+    Ok(BufReader::new(file))
+}
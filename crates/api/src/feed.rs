@@ -0,0 +1,263 @@
+use crate::openapi::ApiDoc;
+use crate::utils::ResponseAPI;
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use actix_ws::Message;
+use game_primitives::feed::{FeedEvent, FeedTopic};
+use game_primitives::{ExitStatus, WorldState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Header sent by SSE clients when reconnecting
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+/// API Endpoints for the live world events
+pub struct Feed;
+
+/// Feed query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FeedQuery {
+    /// Comma separated topics, all topics when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topics: Option<String>,
+    /// Last seen event id, to resume the feed from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+}
+
+impl FeedQuery {
+    /// Parses the requested topics
+    fn topics(&self) -> Result<HashSet<FeedTopic>, String> {
+        match &self.topics {
+            Some(topics) => topics
+                .split(',')
+                .map(str::trim)
+                .filter(|topic| !topic.is_empty())
+                .map(str::parse)
+                .collect(),
+            None => Ok(FeedTopic::ALL.into_iter().collect()),
+        }
+    }
+}
+
+/// Feed subscription
+///
+/// Replays the missed events, then follows the live ones
+/// Ends when the subscriber lags behind or the world exits
+struct Subscription {
+    missed: VecDeque<FeedEvent>,
+    receiver: broadcast::Receiver<FeedEvent>,
+    topics: HashSet<FeedTopic>,
+    exit_status: Arc<ExitStatus>,
+}
+
+impl Subscription {
+    /// Subscribes to the world feed
+    fn new<W: WorldState>(
+        world: &W,
+        query: &FeedQuery,
+        since: Option<u64>,
+    ) -> Result<Self, String> {
+        let topics = query.topics()?;
+        let (missed, receiver) = world.feed().subscribe(since.or(query.since));
+        Ok(Self {
+            missed: missed.into(),
+            receiver,
+            topics,
+            exit_status: world.exit_status(),
+        })
+    }
+
+    /// Waits for the next event matching the topics
+    async fn next(&mut self) -> Option<FeedEvent> {
+        loop {
+            let event = match self.missed.pop_front() {
+                Some(event) => event,
+                None => tokio::select! {
+                    _ = self.exit_status.wait() => return None,
+                    // Lagged subscribers resume with their last seen id
+                    event = self.receiver.recv() => event.ok()?,
+                },
+            };
+
+            if self.topics.contains(&event.topic) {
+                return Some(event);
+            }
+        }
+    }
+}
+
+/// Responds with the invalid query error
+fn invalid_query(error: String, req: &HttpRequest) -> HttpResponse {
+    ResponseAPI::error(error)
+        .with_code(StatusCode::BAD_REQUEST)
+        .respond_to(req)
+}
+
+/// Formats an event as a Server-Sent Event
+fn sse_frame(event: &FeedEvent) -> serde_json::Result<String> {
+    let data = serde_json::to_string(event)?;
+    Ok(format!(
+        "id: {}\nevent: {}\ndata: {data}\n\n",
+        event.id, event.topic
+    ))
+}
+
+impl Feed {
+    /// Streams the world events over a WebSocket
+    pub async fn websocket<W: WorldState>(
+        req: HttpRequest,
+        body: web::Payload,
+        data: web::ThinData<W>,
+        query: web::Query<FeedQuery>,
+    ) -> actix_web::Result<HttpResponse> {
+        let mut subscription = match Subscription::new(&data.0, &query, None) {
+            Ok(subscription) => subscription,
+            Err(e) => return Ok(invalid_query(e, &req)),
+        };
+        let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+
+        actix_web::rt::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = subscription.next() => {
+                        let Some(event) = event else { break };
+                        let Ok(json) = serde_json::to_string(&event) else { continue };
+                        if session.text(json).await.is_err() {
+                            return;
+                        }
+                    }
+                    message = messages.recv() => match message {
+                        Some(Ok(Message::Ping(bytes))) => {
+                            if session.pong(&bytes).await.is_err() {
+                                return;
+                            }
+                        }
+                        Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                        Some(Ok(_)) => {}
+                    },
+                }
+            }
+
+            let _ = session.close(None).await;
+        });
+
+        Ok(response)
+    }
+
+    /// Streams the world events as Server-Sent Events
+    ///
+    /// Reconnecting clients resume from the `Last-Event-ID` header
+    pub async fn events<W: WorldState>(
+        req: HttpRequest,
+        data: web::ThinData<W>,
+        query: web::Query<FeedQuery>,
+    ) -> HttpResponse {
+        let last_event_id = req
+            .headers()
+            .get(LAST_EVENT_ID)
+            .and_then(|id| id.to_str().ok())
+            .and_then(|id| id.parse().ok());
+        let subscription = match Subscription::new(&data.0, &query, last_event_id) {
+            Ok(subscription) => subscription,
+            Err(e) => return invalid_query(e, &req),
+        };
+
+        let stream = futures_util::stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next().await?;
+            let frame = sse_frame(&event).map(web::Bytes::from);
+            Some((frame, subscription))
+        });
+
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+            .streaming(stream)
+    }
+
+    /// Describes the live feed endpoints
+    pub fn document(doc: &mut ApiDoc) {
+        doc.endpoint(
+            Method::GET,
+            "/events",
+            "Live world events as Server-Sent Events",
+        )
+        .query::<FeedQuery>()
+        .stream::<FeedEvent>(StatusCode::OK, "Feed events", "text/event-stream")
+        .error(StatusCode::BAD_REQUEST, "Unknown topic");
+        doc.endpoint(Method::GET, "/ws", "Live world events over a WebSocket")
+            .query::<FeedQuery>()
+            .stream::<FeedEvent>(
+                StatusCode::SWITCHING_PROTOCOLS,
+                "JSON text frames of feed events",
+                "application/json",
+            )
+            .error(StatusCode::BAD_REQUEST, "Unknown topic");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::TestWorld;
+    use actix_web::{App, test};
+    use game_primitives::feed::WorldEvent;
+
+    /// Reads the ids of the streamed events
+    fn event_ids(body: &[u8]) -> Vec<u64> {
+        String::from_utf8_lossy(body)
+            .lines()
+            .filter_map(|line| line.strip_prefix("id: "))
+            .map(|id| id.parse().unwrap())
+            .collect()
+    }
+
+    #[actix_web::test]
+    async fn test_events_resume() {
+        let world = TestWorld::default();
+        let ids = ["alice", "bob", "carol"].map(|player| {
+            let player = player.to_string();
+            world.feed.publish(WorldEvent::PlayerQuit { player })
+        });
+
+        // The stream ends with the missed events once the world exits
+        world.exit_status.exit();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::ThinData(world))
+                .route("/events", web::get().to(Feed::events::<TestWorld>)),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/events?since={}", ids[0]))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        assert_eq!(event_ids(&body), ids[1..]);
+
+        // The header of a reconnecting client wins over the query
+        let request = test::TestRequest::get()
+            .uri(&format!("/events?since={}", ids[0]))
+            .insert_header((LAST_EVENT_ID, ids[1].to_string()))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        assert_eq!(event_ids(&body), ids[2..]);
+
+        // Missed events are filtered by topic too
+        let request = test::TestRequest::get()
+            .uri(&format!("/events?since={}&topics=chat", ids[0]))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        assert!(event_ids(&body).is_empty());
+
+        let request = test::TestRequest::get()
+            .uri("/events?topics=weather")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
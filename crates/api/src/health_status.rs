@@ -1,5 +1,9 @@
+use crate::openapi::ApiDoc;
 use crate::utils::{API_SERVER_VERSION, ResponseAPI};
-use actix_web::Responder;
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Responder, web};
+use game_primitives::WorldState;
+use game_primitives::health::WorldHealth;
 
 pub struct HealthStatus;
 
@@ -11,10 +15,85 @@ impl HealthStatus {
         ResponseAPI::success(API_SERVER_VERSION)
     }
 
-    /// Health Check route
+    /// Liveness route
     ///
-    /// Responds with a simple message
-    pub async fn health_check() -> impl Responder {
-        ResponseAPI::success("OK")
+    /// Responds with an error once the core loop stopped running
+    pub async fn health_check<W: WorldState>(data: web::ThinData<W>) -> impl Responder {
+        match data.health().is_alive() {
+            true => ResponseAPI::success("OK"),
+            false => {
+                ResponseAPI::error("Runner stopped").with_code(StatusCode::SERVICE_UNAVAILABLE)
+            }
+        }
+    }
+
+    /// Readiness route
+    ///
+    /// Responds with the health of the subsystems
+    /// Fails while a critical subsystem is down
+    pub async fn status<W: WorldState>(data: web::ThinData<W>) -> impl Responder {
+        let health = data.health();
+        match health.is_ready() {
+            true => ResponseAPI::success(health),
+            false => ResponseAPI::error(health).with_code(StatusCode::SERVICE_UNAVAILABLE),
+        }
+    }
+
+    /// Fallback route
+    ///
+    /// Responds with a not found error
+    pub async fn not_found() -> impl Responder {
+        ResponseAPI::error("Not Found").with_code(StatusCode::NOT_FOUND)
+    }
+
+    /// Describes the health check endpoints
+    pub fn document(doc: &mut ApiDoc) {
+        doc.endpoint(Method::GET, "/", "Version of the API server")
+            .response::<String>(StatusCode::OK, "The version");
+        doc.endpoint(Method::GET, "/health", "Liveness check")
+            .response::<String>(StatusCode::OK, "The core loop is running")
+            .error(StatusCode::SERVICE_UNAVAILABLE, "The core loop stopped");
+        doc.endpoint(Method::GET, "/status", "Readiness check")
+            .response::<WorldHealth>(StatusCode::OK, "Health of the subsystems")
+            .response::<WorldHealth>(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "A critical subsystem is down",
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::TestWorld;
+    use actix_web::{App, test};
+
+    #[actix_web::test]
+    async fn test_health_endpoints() {
+        for (stopped, code) in [
+            (false, StatusCode::OK),
+            (true, StatusCode::SERVICE_UNAVAILABLE),
+        ] {
+            let world = TestWorld {
+                stopped,
+                ..Default::default()
+            };
+            let app = test::init_service(
+                App::new()
+                    .app_data(web::ThinData(world))
+                    .route(
+                        "/health",
+                        web::get().to(HealthStatus::health_check::<TestWorld>),
+                    )
+                    .route("/status", web::get().to(HealthStatus::status::<TestWorld>)),
+            )
+            .await;
+
+            for uri in ["/health", "/status"] {
+                let request = test::TestRequest::get().uri(uri).to_request();
+                let response = test::call_service(&app, request).await;
+                assert_eq!(response.status(), code, "{uri}");
+            }
+        }
     }
 }
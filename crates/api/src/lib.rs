@@ -1,54 +1,208 @@
-use actix_web::web;
-use actix_web::{App, HttpServer};
-use game_primitives::WorldState;
+use actix_web::dev::Server;
+use actix_web::{App, HttpServer, web};
+use anyhow::{Result, anyhow};
+use game_primitives::admin::{AdminCommand, LogFilter};
+use game_primitives::events::{GameEvent, Traced};
+use game_primitives::{Player, Position, WorldState};
+use prometheus_client::registry::Registry;
+use schemars::JsonSchema;
 use serde::Serialize;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::mpsc::Sender;
 
+mod admin;
+mod auth;
+mod config;
+mod feed;
 mod health_status;
+mod metrics;
+#[cfg(test)]
+mod mock;
+mod openapi;
 mod utils;
+mod world_input;
 mod world_status;
 
+use admin::Admin;
+pub use admin::{BanQuery, DialRequest, LogRequest, MinerRequest};
+use auth::ApiToken;
+pub use config::{API_TOKEN_PATH, ApiConfig, TlsConfig};
+use feed::Feed;
+pub use feed::FeedQuery;
 use health_status::HealthStatus;
+pub use metrics::MetricsServer;
+use openapi::ApiDoc;
+pub use utils::{Page, ResponseAPI, ResponseStatus};
+use world_input::WorldInput;
+pub use world_input::{ChatRequest, Direction, MoveRequest};
 use world_status::WorldStatus;
+pub use world_status::{ChatQuery, MineQuery, MinerShares, PlayersQuery};
 
 /// Api Server
 ///
 /// Responsible for running the API server
-pub struct ApiServer<W: WorldState> {
+pub struct ApiServer<W: WorldState, F, Po, J> {
     world: web::ThinData<W>,
+    input: web::ThinData<Sender<Traced<GameEvent<F, Po, J>>>>,
+    admin: web::ThinData<tokio::sync::mpsc::Sender<AdminCommand>>,
+    log_filter: Option<LogFilter>,
+    registries: Vec<Arc<Registry>>,
+    config: ApiConfig,
 }
 
-impl<W, M, P> ApiServer<W>
+impl<W, M, P, B, F, Po, J> ApiServer<W, F, Po, J>
 where
-    W: WorldState<Message = M, Player = P> + Clone + Send + Sync + 'static,
-    M: Serialize + 'static,
-    P: Serialize + 'static,
+    W: WorldState<Message = M, Player = P, MiningBatch = B>
+        + Serialize
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    W::Id: Display,
+    M: Serialize + JsonSchema + 'static,
+    P: Player + Serialize + JsonSchema + 'static,
+    B: JsonSchema + 'static,
+    F: Send + 'static,
+    Po: Position<Unit = i32> + Send + 'static,
+    J: Send + 'static,
 {
     /// Creates a new API server that holds resources
-    pub fn new(world: W) -> Self {
+    ///
+    /// The write endpoints send the local player events to `input`
+    /// and the admin endpoints send the operator commands to `admin`
+    pub fn new(
+        world: W,
+        input: Sender<Traced<GameEvent<F, Po, J>>>,
+        admin: tokio::sync::mpsc::Sender<AdminCommand>,
+        config: ApiConfig,
+    ) -> Self {
         let world = web::ThinData(world);
-        Self { world }
+        let input = web::ThinData(input);
+        let admin = web::ThinData(admin);
+        Self {
+            world,
+            input,
+            admin,
+            log_filter: None,
+            registries: Vec::new(),
+            config,
+        }
     }
 
-    /// Runs the API server
-    pub async fn run(self) {
+    /// Lets the admin endpoints change the log filter
+    pub fn with_log_filter(mut self, filter: LogFilter) -> Self {
+        self.log_filter = Some(filter);
+        self
+    }
+
+    /// Exports the metrics of a registry on `/metrics`
+    pub fn with_metrics(mut self, registry: Arc<Registry>) -> Self {
+        self.registries.push(registry);
+        self
+    }
+
+    /// Binds the API server
+    ///
+    /// Returns the server to await, it stops gracefully once the world exits
+    pub fn bind(self) -> Result<Server> {
+        let exit_status = self.world.0.exit_status();
+        let (world, input, admin) = (self.world.clone(), self.input.clone(), self.admin.clone());
+        let log_filter = web::ThinData(self.log_filter);
+        let token = ApiToken::new(self.config.token.clone());
+        let document = web::ThinData(Arc::new(Self::document()));
+        let registries = web::ThinData(Arc::new(self.registries));
         let app = move || {
             App::new()
                 .wrap(actix_cors::Cors::permissive())
-                .app_data(self.world.clone())
+                .app_data(world.clone())
+                .app_data(input.clone())
+                .app_data(admin.clone())
+                .app_data(log_filter.clone())
+                .app_data(token.clone())
+                .app_data(document.clone())
+                .app_data(registries.clone())
+                .app_data(web::QueryConfig::default().error_handler(utils::bad_request))
+                .app_data(web::JsonConfig::default().error_handler(utils::bad_request))
                 // Health Check Endpoints
                 .service(web::resource("/").to(HealthStatus::index))
-                .service(web::resource("/health").to(HealthStatus::health_check))
+                .service(web::resource("/health").to(HealthStatus::health_check::<W>))
+                .service(web::resource("/status").to(HealthStatus::status::<W>))
+                // Metrics Endpoints
+                .service(web::resource("/metrics").to(MetricsServer::metrics))
+                // Api Description Endpoints
+                .service(web::resource("/openapi.json").to(ApiDoc::serve))
                 // World Status Endpoints
                 .service(web::resource("/mine").to(WorldStatus::mined_batch::<W>))
+                .service(web::resource("/mine/{address}").to(WorldStatus::miner_shares::<W>))
                 .service(web::resource("/players").to(WorldStatus::players::<W, P>))
-                .service(web::resource("/chat").to(WorldStatus::chat_messages::<W, M>))
+                .service(web::resource("/players/{address}").to(WorldStatus::player::<W, P>))
+                .service(
+                    web::resource("/chat")
+                        .route(web::get().to(WorldStatus::chat_messages::<W>))
+                        .route(web::post().to(WorldInput::chat::<F, Po, J>)),
+                )
+                .service(web::resource("/bans").to(WorldStatus::bans::<W>))
+                // World Input Endpoints, authenticated with the bearer token
+                .service(
+                    web::resource("/move")
+                        .route(web::post().to(WorldInput::move_player::<F, Po, J>)),
+                )
+                .service(web::resource("/quit").route(web::post().to(WorldInput::quit::<F, Po, J>)))
+                // Live Feed Endpoints
+                .service(web::resource("/ws").to(Feed::websocket::<W>))
+                .service(web::resource("/events").to(Feed::events::<W>))
+                // Admin Endpoints, authenticated with the bearer token
+                .service(
+                    web::scope("/admin")
+                        .service(
+                            web::resource("/miner")
+                                .route(web::get().to(Admin::miner))
+                                .route(web::post().to(Admin::configure_miner)),
+                        )
+                        .service(web::resource("/claim").route(web::post().to(Admin::claim)))
+                        .service(web::resource("/peers").route(web::get().to(Admin::peers)))
+                        .service(
+                            web::resource("/peers/{peer_id}/ban")
+                                .route(web::post().to(Admin::ban_peer)),
+                        )
+                        .service(web::resource("/dial").route(web::post().to(Admin::dial)))
+                        .service(web::resource("/log").route(web::put().to(Admin::log_filter)))
+                        .service(
+                            web::resource("/snapshot").route(web::get().to(Admin::snapshot::<W>)),
+                        ),
+                )
+                .default_service(web::to(HealthStatus::not_found))
         };
 
-        HttpServer::new(app)
-            .bind("0.0.0.0:8080")
-            .expect("Failed to bind to port 8080")
-            .run()
-            .await
-            .expect("Failed to run server");
+        let server = HttpServer::new(app).disable_signals();
+        let server = match &self.config.tls {
+            Some(tls) => server.bind_rustls_0_23(&self.config.bind, tls.server_config()?),
+            None => server.bind(&self.config.bind),
+        }
+        .map_err(|e| anyhow!("Failed to bind API server to {}: {e}", self.config.bind))?
+        .run();
+
+        // Stop the server on world exit
+        let handle = server.handle();
+        tokio::spawn(async move {
+            exit_status.wait().await;
+            handle.stop(true).await;
+        });
+
+        Ok(server)
+    }
+
+    /// Builds the OpenAPI document of the endpoints
+    fn document() -> serde_json::Value {
+        let mut doc = ApiDoc::new();
+        HealthStatus::document(&mut doc);
+        ApiDoc::document(&mut doc);
+        MetricsServer::document(&mut doc);
+        WorldStatus::document::<M, P, B>(&mut doc);
+        WorldInput::document(&mut doc);
+        Feed::document(&mut doc);
+        Admin::document(&mut doc);
+        doc.build()
     }
 }
@@ -0,0 +1,80 @@
+use crate::openapi::ApiDoc;
+use actix_web::dev::Server;
+use actix_web::http::{Method, StatusCode};
+use actix_web::{App, HttpResponse, HttpServer, Responder, web};
+use anyhow::{Result, anyhow};
+use prometheus_client::encoding::text::{encode_eof, encode_registry};
+use prometheus_client::registry::Registry;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Content type of the OpenMetrics text format
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Metrics registries exported together
+pub(crate) type Registries = web::ThinData<Arc<Vec<Arc<Registry>>>>;
+
+/// Metrics Server
+///
+/// Serves the network metrics in the OpenMetrics text format
+pub struct MetricsServer {
+    registry: Registries,
+    bind: String,
+}
+
+impl MetricsServer {
+    /// Creates a new metrics server for the registry
+    pub fn new(registry: Arc<Registry>, bind: impl Into<String>) -> Self {
+        let registry = web::ThinData(Arc::new(vec![registry]));
+        let bind = bind.into();
+        Self { registry, bind }
+    }
+
+    /// Binds the metrics server
+    ///
+    /// Returns the server to await
+    pub fn bind(self) -> Result<Server> {
+        let registry = self.registry.clone();
+        let app = move || {
+            App::new()
+                .app_data(registry.clone())
+                .service(web::resource("/metrics").to(Self::metrics))
+        };
+
+        let server = HttpServer::new(app)
+            .disable_signals()
+            .bind(&self.bind)
+            .map_err(|e| anyhow!("Failed to bind metrics server to {}: {e}", self.bind))?
+            .run();
+
+        Ok(server)
+    }
+
+    /// Responds with the encoded metrics of every registry
+    pub(crate) async fn metrics(registries: Registries) -> impl Responder {
+        let mut buffer = String::new();
+        let encoded = registries
+            .iter()
+            .try_for_each(|registry| encode_registry(&mut buffer, registry))
+            .and_then(|_| encode_eof(&mut buffer));
+
+        match encoded {
+            Ok(_) => HttpResponse::Ok()
+                .content_type(OPENMETRICS_CONTENT_TYPE)
+                .body(buffer),
+            Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+        }
+    }
+
+    /// Describes the metrics endpoint
+    pub(crate) fn document(doc: &mut ApiDoc) {
+        let schema = json!({"type": "string"});
+        doc.endpoint(Method::GET, "/metrics", "Prometheus metrics of the node")
+            .content(
+                StatusCode::OK,
+                "OpenMetrics text",
+                OPENMETRICS_CONTENT_TYPE,
+                schema,
+            );
+    }
+}
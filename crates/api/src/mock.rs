@@ -0,0 +1,199 @@
+use crate::auth::ApiToken;
+use game_primitives::feed::EventFeed;
+use game_primitives::health::{
+    CheckStatus, ClaimsHealth, MinerHealth, NetworkHealth, RpcHealth, RunnerHealth, WorldHealth,
+};
+use game_primitives::map::{MapTile, MinedShare, ZoneStatus};
+use game_primitives::message::ChatMessage;
+use game_primitives::reputation::BanEntry;
+use game_primitives::{ExitStatus, Identifier, Player, Position, WorldMap, WorldState};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Bearer token of the test servers
+pub const TOKEN: &str = "secret";
+
+/// Gets the token of the test servers
+pub fn token() -> ApiToken {
+    ApiToken::new(Some(TOKEN.to_string()))
+}
+
+/// Gets the authorization header of the test requests
+pub fn bearer() -> (&'static str, String) {
+    ("Authorization", format!("Bearer {TOKEN}"))
+}
+
+/// Point
+///
+/// Position of the test players
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point(pub i32, pub i32);
+
+impl Position for Point {
+    type Unit = i32;
+
+    fn new(x: i32, y: i32) -> Self {
+        Self(x, y)
+    }
+
+    fn x(&self) -> f64 {
+        self.0 as f64
+    }
+
+    fn y(&self) -> f64 {
+        self.1 as f64
+    }
+}
+
+/// Test Player
+///
+/// Standing at the origin
+pub struct TestPlayer;
+
+impl Identifier for TestPlayer {
+    type Id = String;
+
+    fn identifier(&self) -> Self::Id {
+        String::new()
+    }
+}
+
+impl Player for TestPlayer {
+    type Position = Point;
+
+    fn position(&self) -> Self::Position {
+        Point(0, 0)
+    }
+}
+
+/// Test Map
+///
+/// Without any tile
+pub struct TestMap;
+
+impl WorldMap for TestMap {
+    fn tile_size(&self) -> (u32, u32) {
+        (16, 16)
+    }
+
+    fn tiles(&self) -> Vec<MapTile> {
+        Vec::new()
+    }
+}
+
+/// Test World
+///
+/// Serves chat messages, shares and a live feed, without players
+/// Each share counts one unit of work
+/// Healthy unless stopped, then its runner and rpc are down
+#[derive(Clone, Default)]
+pub struct TestWorld {
+    pub stopped: bool,
+    pub exit_status: Arc<ExitStatus>,
+    pub feed: Arc<EventFeed>,
+    pub messages: Vec<ChatMessage>,
+    pub shares: Vec<MinedShare>,
+}
+
+impl Identifier for TestWorld {
+    type Id = String;
+
+    fn identifier(&self) -> Self::Id {
+        String::new()
+    }
+}
+
+impl WorldState for TestWorld {
+    type Player = TestPlayer;
+    type Message = ChatMessage;
+    type MiningBatch = ChatMessage;
+    type Map = TestMap;
+
+    fn exit_status(&self) -> Arc<ExitStatus> {
+        self.exit_status.clone()
+    }
+
+    fn get_all_players(&self) -> HashMap<Self::Id, Self::Player> {
+        HashMap::new()
+    }
+
+    fn get_mining_rewards_count(&self) -> u32 {
+        0
+    }
+
+    fn get_mining_batch(&self) -> HashSet<Self::MiningBatch> {
+        HashSet::new()
+    }
+
+    fn get_chat_messages(&self) -> Vec<Self::Message> {
+        self.messages.clone()
+    }
+
+    fn mute(&self, _: &str, _: bool) -> bool {
+        false
+    }
+
+    fn block(&self, _: &str, _: bool) -> bool {
+        false
+    }
+
+    fn get_map(&self) -> Arc<Self::Map> {
+        Arc::new(TestMap)
+    }
+
+    fn get_zones(&self) -> Vec<ZoneStatus> {
+        Vec::new()
+    }
+
+    fn get_miner_contribution(&self, miner: &str) -> u64 {
+        self.get_miner_shares(miner).len() as u64
+    }
+
+    fn get_miner_shares(&self, miner: &str) -> Vec<MinedShare> {
+        let shares = self.shares.iter().filter(|share| share.miner == miner);
+        shares.cloned().collect()
+    }
+
+    fn get_bans(&self) -> Vec<BanEntry> {
+        Vec::new()
+    }
+
+    fn feed(&self) -> Arc<EventFeed> {
+        self.feed.clone()
+    }
+
+    fn record_fps(&self, _: f64) {}
+
+    fn health(&self) -> WorldHealth {
+        let status = match self.stopped {
+            true => CheckStatus::Down,
+            false => CheckStatus::Up,
+        };
+        let runner = RunnerHealth {
+            status,
+            last_tick: 0,
+        };
+        let rpc = RpcHealth {
+            status,
+            block: Some(1),
+            last_seen: Some(0),
+            error: None,
+        };
+        let network = NetworkHealth {
+            status: CheckStatus::Up,
+            peers: 1,
+            last_gossip: Some(0),
+        };
+        let miner = MinerHealth {
+            status: CheckStatus::Up,
+            enabled: false,
+            running: false,
+        };
+        let claims = ClaimsHealth {
+            status: CheckStatus::Up,
+            pending: 0,
+            in_flight: 0,
+        };
+        WorldHealth::new(0, runner, rpc, network, miner, claims)
+    }
+}
@@ -0,0 +1,198 @@
+use crate::utils::{API_SERVER_VERSION, ResponseAPI};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{HttpResponse, web};
+use schemars::JsonSchema;
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use serde_json::{Map, Value, json};
+use std::sync::Arc;
+
+/// Title of the OpenAPI document
+const API_TITLE: &str = "Fonketh Node API";
+
+/// Api Doc
+///
+/// OpenAPI 3 document of the API server
+/// Handlers describe their endpoints, the schemas are generated from their types
+pub struct ApiDoc {
+    generator: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl Default for ApiDoc {
+    fn default() -> Self {
+        Self {
+            generator: SchemaSettings::openapi3().into_generator(),
+            paths: Map::new(),
+        }
+    }
+}
+
+impl ApiDoc {
+    /// Creates an empty document
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Describes an endpoint
+    ///
+    /// Path parameters are taken from the `{name}` segments
+    pub fn endpoint(&mut self, method: Method, path: &str, summary: &str) -> Endpoint<'_> {
+        let parameters = path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}}))
+            .collect::<Vec<_>>();
+
+        let operation = self
+            .paths
+            .entry(path)
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("path item is an object")
+            .entry(method.as_str().to_lowercase())
+            .or_insert_with(
+                || json!({"summary": summary, "parameters": parameters, "responses": {}}),
+            )
+            .as_object_mut()
+            .expect("operation is an object");
+
+        Endpoint {
+            generator: &mut self.generator,
+            operation,
+        }
+    }
+
+    /// Builds the OpenAPI document
+    pub fn build(mut self) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": API_TITLE, "version": API_SERVER_VERSION},
+            "paths": self.paths,
+            "components": {
+                "schemas": self.generator.take_definitions(true),
+                "securitySchemes": {"bearer": {"type": "http", "scheme": "bearer"}},
+            },
+        })
+    }
+
+    /// Serves the OpenAPI document
+    pub async fn serve(document: web::ThinData<Arc<Value>>) -> HttpResponse {
+        HttpResponse::Ok().json(&*document.0)
+    }
+
+    /// Describes the document endpoint
+    pub fn document(doc: &mut ApiDoc) {
+        let schema = json!({"type": "object"});
+        doc.endpoint(Method::GET, "/openapi.json", "OpenAPI document of the API")
+            .content(
+                StatusCode::OK,
+                "OpenAPI 3 document",
+                "application/json",
+                schema,
+            );
+    }
+}
+
+/// Endpoint
+///
+/// Operation of the OpenAPI document being described
+pub struct Endpoint<'a> {
+    generator: &'a mut SchemaGenerator,
+    operation: &'a mut Map<String, Value>,
+}
+
+impl Endpoint<'_> {
+    /// Documents the query parameters from the fields of `Q`
+    pub fn query<Q: JsonSchema>(mut self) -> Self {
+        let schema = self.generator.root_schema_for::<Q>();
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        let parameters = self.parameters();
+        for (name, mut schema) in properties {
+            let description = schema.as_object_mut().and_then(|s| s.remove("description"));
+            parameters.push(json!({
+                "name": name,
+                "in": "query",
+                "required": required.contains(&Value::String(name.clone())),
+                "description": description.unwrap_or_default(),
+                "schema": schema,
+            }));
+        }
+        self
+    }
+
+    /// Documents the JSON request body
+    pub fn body<B: JsonSchema>(self) -> Self {
+        let schema = self.generator.subschema_for::<B>();
+        self.operation.insert(
+            "requestBody".into(),
+            json!({"required": true, "content": {"application/json": {"schema": schema}}}),
+        );
+        self
+    }
+
+    /// Documents a response wrapped in `ResponseAPI`
+    pub fn response<R: JsonSchema>(self, status: StatusCode, description: &str) -> Self {
+        let schema = self.generator.subschema_for::<ResponseAPI<R>>();
+        self.content(status, description, "application/json", schema.into())
+    }
+
+    /// Documents an error response
+    pub fn error(self, status: StatusCode, description: &str) -> Self {
+        self.response::<String>(status, description)
+    }
+
+    /// Documents a streamed response of `T` items
+    pub fn stream<T: JsonSchema>(self, status: StatusCode, description: &str, media: &str) -> Self {
+        let schema = self.generator.subschema_for::<T>();
+        self.content(status, description, media, schema.into())
+    }
+
+    /// Documents a response that is not wrapped in `ResponseAPI`
+    pub fn content(
+        mut self,
+        status: StatusCode,
+        description: &str,
+        media: &str,
+        schema: Value,
+    ) -> Self {
+        let response = json!({"description": description, "content": {media: {"schema": schema}}});
+        self.responses()
+            .insert(status.as_u16().to_string(), response);
+        self
+    }
+
+    /// Requires the bearer token
+    pub fn bearer(self) -> Self {
+        self.operation
+            .insert("security".into(), json!([{"bearer": []}]));
+        self.error(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token")
+    }
+
+    /// Parameters of the operation
+    fn parameters(&mut self) -> &mut Vec<Value> {
+        self.operation
+            .entry("parameters")
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .expect("parameters are an array")
+    }
+
+    /// Responses of the operation
+    fn responses(&mut self) -> &mut Map<String, Value> {
+        self.operation
+            .entry("responses")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("responses are an object")
+    }
+}
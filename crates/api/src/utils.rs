@@ -1,12 +1,19 @@
+use actix_web::Responder;
+use actix_web::http::StatusCode;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// API SERVER VERSION
 pub const API_SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Default number of items per page
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+/// Maximum number of items per page
+pub const MAX_PAGE_SIZE: usize = 1000;
 
 /// Response Status
 ///
 /// Used to represent the status of the response
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, JsonSchema)]
 pub enum ResponseStatus {
     Success,
     Error,
@@ -15,26 +22,40 @@ pub enum ResponseStatus {
 /// Response API
 ///
 /// Used to represent the response API
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "ResponseAPI_{D}")]
 pub struct ResponseAPI<D> {
     pub status: ResponseStatus,
     pub data: D,
+    /// HTTP status code of the response
+    #[serde(skip)]
+    code: StatusCode,
 }
 
 impl<D> ResponseAPI<D> {
     /// Creates a new response API
-    fn new(status: ResponseStatus, data: D) -> Self {
-        Self { status, data }
+    fn new(status: ResponseStatus, data: D, code: StatusCode) -> Self {
+        Self { status, data, code }
     }
 
     /// Creates a new success response API
     pub fn success(data: D) -> Self {
-        Self::new(ResponseStatus::Success, data)
+        Self::new(ResponseStatus::Success, data, StatusCode::OK)
     }
 
     /// Creates a new error response API
     pub fn error(data: D) -> Self {
-        Self::new(ResponseStatus::Error, data)
+        Self::new(
+            ResponseStatus::Error,
+            data,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    }
+
+    /// Sets the HTTP status code of the response
+    pub fn with_code(mut self, code: StatusCode) -> Self {
+        self.code = code;
+        self
     }
 }
 
@@ -45,6 +66,76 @@ where
     type Body = actix_web::body::BoxBody;
 
     fn respond_to(self, _req: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
-        actix_web::HttpResponse::Ok().json(self)
+        actix_web::HttpResponse::build(self.code).json(self)
+    }
+}
+
+/// Page
+///
+/// Slice of a listing, with the offset of the next slice
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Page_{T}")]
+pub struct Page<T> {
+    /// Items of the page
+    pub items: Vec<T>,
+    /// Number of items matching the filters
+    pub total: usize,
+    /// Offset of the next page, absent on the last page
+    pub next: Option<usize>,
+}
+
+impl<T> Page<T> {
+    /// Pages through the matching items
+    pub fn new(items: Vec<T>, offset: Option<usize>, limit: Option<usize>) -> Self {
+        let total = items.len();
+        let offset = offset.unwrap_or(0).min(total);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        let items = items
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect::<Vec<_>>();
+        let next = Some(offset + items.len()).filter(|next| *next < total);
+        Self { items, total, next }
+    }
+}
+
+/// Responds to malformed query parameters or bodies
+pub fn bad_request<E>(error: E, req: &actix_web::HttpRequest) -> actix_web::Error
+where
+    E: std::fmt::Debug + std::fmt::Display + 'static,
+{
+    let response = ResponseAPI::error(error.to_string())
+        .with_code(StatusCode::BAD_REQUEST)
+        .respond_to(req);
+    actix_web::error::InternalError::from_response(error, response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let page = Page::new((0..250).collect(), None, None);
+        assert_eq!((page.items.len(), page.total), (DEFAULT_PAGE_SIZE, 250));
+        assert_eq!(page.next, Some(DEFAULT_PAGE_SIZE));
+
+        // The last page has no next offset
+        let page = Page::new((0..250).collect(), Some(200), Some(100));
+        assert_eq!(page.items, (200..250).collect::<Vec<_>>());
+        assert_eq!(page.next, None);
+
+        // Offsets past the end give an empty page
+        let page = Page::new((0..10).collect::<Vec<u32>>(), Some(20), None);
+        assert!(page.items.is_empty());
+        assert_eq!((page.total, page.next), (10, None));
+
+        // Limits are clamped between one and the maximum
+        let page = Page::new((0..10).collect(), Some(3), Some(0));
+        assert_eq!((page.items, page.next), (vec![3], Some(4)));
+        let page = Page::new((0..2000).collect::<Vec<u32>>(), None, Some(5000));
+        assert_eq!(page.items.len(), MAX_PAGE_SIZE);
     }
 }
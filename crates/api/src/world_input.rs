@@ -0,0 +1,258 @@
+use crate::auth::Authorized;
+use crate::openapi::ApiDoc;
+use crate::utils::ResponseAPI;
+use actix_web::Responder;
+use actix_web::http::{Method, StatusCode};
+use actix_web::web;
+use game_primitives::Position;
+use game_primitives::events::{GameEvent, Traced};
+use game_primitives::message::{ChatText, GLOBAL_CHANNEL, MAX_CHANNEL_LENGTH, MAX_MESSAGE_LENGTH};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::Sender;
+
+/// API Endpoints acting on the world as the local player
+///
+/// Events go through the same channel as the keyboard, so the world
+/// applies, signs and gossips them
+pub struct WorldInput;
+
+/// Local input channel of the world
+pub type InputSender<F, Po, J> = web::ThinData<Sender<Traced<GameEvent<F, Po, J>>>>;
+
+/// Movement direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Move request body
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveRequest {
+    /// Direction of a single step
+    pub direction: Direction,
+}
+
+/// Chat request body
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChatRequest {
+    /// Message text
+    pub text: String,
+    /// Channel name of 1 to 32 bytes, global when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Address or ENS name of the whisper recipient
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+impl ChatRequest {
+    /// Builds the chat event of the request
+    fn event<F, Po, J>(self) -> Result<GameEvent<F, Po, J>, String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Err("Empty chat message".into());
+        }
+        if text.len() > MAX_MESSAGE_LENGTH {
+            return Err(format!("Chat message over {MAX_MESSAGE_LENGTH} bytes"));
+        }
+
+        if let Some(channel) = &self.channel
+            && (channel.is_empty() || channel.len() > MAX_CHANNEL_LENGTH)
+        {
+            return Err(format!(
+                "Channel name must be 1 to {MAX_CHANNEL_LENGTH} bytes"
+            ));
+        }
+
+        let text = text.to_string();
+        match (self.channel, self.to) {
+            (Some(_), Some(_)) => Err("Set either a channel or a recipient".into()),
+            (None, Some(to)) => Ok(GameEvent::Whisper(to, text)),
+            (Some(channel), None) if channel != GLOBAL_CHANNEL => {
+                Ok(GameEvent::ChannelMessage(channel, ChatText::new(text)))
+            }
+            _ => Ok(GameEvent::ChatMessage(ChatText::new(text))),
+        }
+    }
+}
+
+/// Hands an event over to the world, within the span of the request
+fn submit<F, Po, J>(
+    sender: &InputSender<F, Po, J>,
+    event: GameEvent<F, Po, J>,
+) -> ResponseAPI<String> {
+    let _span = tracing::info_span!("api_input", event_type = event.name()).entered();
+    match sender.send(Traced::new(event)) {
+        Ok(()) => ResponseAPI::success("Accepted".to_string()).with_code(StatusCode::ACCEPTED),
+        Err(_) => ResponseAPI::error("World is shutting down".to_string())
+            .with_code(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+impl WorldInput {
+    /// Moves the local player by one step
+    pub async fn move_player<F, Po: Position<Unit = i32>, J>(
+        _: Authorized,
+        sender: InputSender<F, Po, J>,
+        body: web::Json<MoveRequest>,
+    ) -> impl Responder {
+        let (x, y) = match body.direction {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+        submit(&sender, GameEvent::PlayerMovement(Po::new(x, y)))
+    }
+
+    /// Sends a chat message, to a channel or as a whisper
+    pub async fn chat<F, Po, J>(
+        _: Authorized,
+        sender: InputSender<F, Po, J>,
+        body: web::Json<ChatRequest>,
+    ) -> impl Responder {
+        match body.into_inner().event() {
+            Ok(event) => submit(&sender, event),
+            Err(e) => ResponseAPI::error(e).with_code(StatusCode::BAD_REQUEST),
+        }
+    }
+
+    /// Removes the local player from the world
+    pub async fn quit<F, Po, J>(_: Authorized, sender: InputSender<F, Po, J>) -> impl Responder {
+        submit(&sender, GameEvent::Quit)
+    }
+
+    /// Describes the world input endpoints
+    pub fn document(doc: &mut ApiDoc) {
+        doc.endpoint(Method::POST, "/move", "Moves the local player by one step")
+            .body::<MoveRequest>()
+            .response::<String>(StatusCode::ACCEPTED, "Movement queued")
+            .error(StatusCode::SERVICE_UNAVAILABLE, "World shutting down")
+            .bearer();
+        doc.endpoint(
+            Method::POST,
+            "/chat",
+            "Sends a chat message as the local player",
+        )
+        .body::<ChatRequest>()
+        .response::<String>(StatusCode::ACCEPTED, "Message queued")
+        .error(StatusCode::BAD_REQUEST, "Invalid message")
+        .error(StatusCode::SERVICE_UNAVAILABLE, "World shutting down")
+        .bearer();
+        doc.endpoint(
+            Method::POST,
+            "/quit",
+            "Removes the local player from the world",
+        )
+        .response::<String>(StatusCode::ACCEPTED, "Quit queued")
+        .error(StatusCode::SERVICE_UNAVAILABLE, "World shutting down")
+        .bearer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{self, Point};
+    use crate::utils;
+    use actix_web::App;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use std::sync::mpsc;
+
+    type Event = GameEvent<(), Point, ()>;
+
+    fn chat(text: &str, channel: Option<&str>, to: Option<&str>) -> Result<Event, String> {
+        let request = ChatRequest {
+            text: text.to_string(),
+            channel: channel.map(Into::into),
+            to: to.map(Into::into),
+        };
+        request.event()
+    }
+
+    #[test]
+    fn test_chat_request() {
+        assert!(chat("  ", None, None).is_err());
+        assert!(chat(&"a".repeat(MAX_MESSAGE_LENGTH + 1), None, None).is_err());
+        assert!(chat("hi", Some("dev"), Some("vitalik.eth")).is_err());
+        assert!(chat("hi", Some(""), None).is_err());
+        assert!(chat("hi", Some(&"a".repeat(MAX_CHANNEL_LENGTH + 1)), None).is_err());
+
+        assert!(
+            matches!(chat(" hi ", None, None), Ok(GameEvent::ChatMessage(t)) if t.text == "hi")
+        );
+        assert!(matches!(
+            chat("hi", Some(GLOBAL_CHANNEL), None),
+            Ok(GameEvent::ChatMessage(_))
+        ));
+        assert!(matches!(
+            chat("hi", Some("dev"), None),
+            Ok(GameEvent::ChannelMessage(channel, _)) if channel == "dev"
+        ));
+        assert_eq!(
+            chat("hi", None, Some("vitalik.eth")),
+            Ok(GameEvent::Whisper("vitalik.eth".into(), "hi".into()))
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_chat_endpoint() {
+        let (sender, receiver) = mpsc::channel::<Traced<Event>>();
+        let app = init_service(
+            App::new()
+                .app_data(mock::token())
+                .app_data(web::ThinData(sender))
+                .app_data(web::JsonConfig::default().error_handler(utils::bad_request))
+                .route("/chat", web::post().to(WorldInput::chat::<(), Point, ()>)),
+        )
+        .await;
+        let body = ChatRequest {
+            text: "gm".into(),
+            ..Default::default()
+        };
+
+        let request = TestRequest::post()
+            .uri("/chat")
+            .set_json(&body)
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(receiver.try_recv().is_err());
+
+        // Accepted messages are handed over to the world
+        let request = TestRequest::post()
+            .uri("/chat")
+            .insert_header(mock::bearer())
+            .set_json(&body)
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert!(matches!(
+            receiver.try_recv().unwrap().event,
+            GameEvent::ChatMessage(_)
+        ));
+
+        // Invalid messages and bodies are rejected
+        let request = TestRequest::post()
+            .uri("/chat")
+            .insert_header(mock::bearer())
+            .set_json(ChatRequest::default())
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let request = TestRequest::post()
+            .uri("/chat")
+            .insert_header(mock::bearer())
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload("{\"message\": 1}")
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(receiver.try_recv().is_err());
+    }
+}
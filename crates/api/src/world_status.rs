@@ -1,32 +1,316 @@
-use crate::utils::ResponseAPI;
-use actix_web::Responder;
-use actix_web::web;
-use game_primitives::WorldState;
-use serde::Serialize;
+use crate::auth::Authorized;
+use crate::openapi::ApiDoc;
+use crate::utils::{Page, ResponseAPI};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Either, Responder, web};
+use game_primitives::map::MinedShare;
+use game_primitives::message::ChatScope;
+use game_primitives::reputation::BanEntry;
+use game_primitives::{Player, Position, Record, Scoped, WorldState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Default half size of the box around a position
+const DEFAULT_RADIUS: f64 = 16.0;
 
 /// API Endpoints for the world status
 pub struct WorldStatus;
 
+/// Chat query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChatQuery {
+    /// Channel name, `global` or `direct`, direct messages need the bearer token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Sender address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Only messages written after this unix time in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    /// Maximum number of messages, up to 1000
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of messages to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
+/// Players query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PlayersQuery {
+    /// Player address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Horizontal position, set together with `y`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<f64>,
+    /// Vertical position, set together with `x`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<f64>,
+    /// Half size of the box around the position, defaults to 16 tiles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+    /// Maximum number of players, up to 1000
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of players to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
+/// Mined shares query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MineQuery {
+    /// Miner address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Only shares mined after this unix time in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    /// Maximum number of shares, up to 1000
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Number of shares to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
+/// Miner Shares
+///
+/// Accounted work of a miner and a page of its remembered shares
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MinerShares {
+    /// Miner address
+    pub miner: String,
+    /// Accumulated work of the accounted shares
+    pub contribution: u64,
+    /// Shares still remembered, oldest first
+    pub shares: Page<MinedShare>,
+}
+
+/// Checks a record against the address and time filters
+fn matches<R: Record>(record: &R, address: Option<&str>, since: Option<u64>) -> bool {
+    address.is_none_or(|address| record.owner().eq_ignore_ascii_case(address))
+        && since.is_none_or(|since| record.timestamp() > since)
+}
+
 impl WorldStatus {
-    /// Responds with latest state of the world.
-    pub async fn mined_batch<W: WorldState>(data: web::ThinData<W>) -> impl Responder {
-        let batch = data.get_mining_batch();
-        ResponseAPI::success(batch)
+    /// Responds with the mined shares of the current batch
+    ///
+    /// Optionally filtered by miner and time
+    pub async fn mined_batch<W: WorldState>(
+        data: web::ThinData<W>,
+        query: web::Query<MineQuery>,
+    ) -> impl Responder {
+        let address = query.address.as_deref();
+        ResponseAPI::success(Self::mined(&data.0, address, &query))
     }
 
-    /// Responds with players in the world
-    pub async fn players<W: WorldState<Player = P>, P: Serialize>(
+    /// Responds with the accounted work and shares of a miner
+    ///
+    /// Optionally filtered by time
+    pub async fn miner_shares<W: WorldState>(
         data: web::ThinData<W>,
+        address: web::Path<String>,
+        query: web::Query<MineQuery>,
     ) -> impl Responder {
-        let players = data.get_all_players().into_values().collect::<Vec<_>>();
-        ResponseAPI::success(players)
+        let miner = address.into_inner();
+        let shares = data
+            .get_miner_shares(&miner)
+            .into_iter()
+            .filter(|share| matches(share, None, query.since))
+            .collect();
+
+        ResponseAPI::success(MinerShares {
+            contribution: data.get_miner_contribution(&miner),
+            shares: Page::new(shares, query.offset, query.limit),
+            miner,
+        })
+    }
+
+    /// Pages through the mined shares, oldest first
+    fn mined<W: WorldState>(
+        world: &W,
+        address: Option<&str>,
+        query: &MineQuery,
+    ) -> Page<W::MiningBatch> {
+        let mut batch = world
+            .get_mining_batch()
+            .into_iter()
+            .filter(|share| matches(share, address, query.since))
+            .collect::<Vec<_>>();
+        batch.sort_by_cached_key(|share| (share.timestamp(), share.owner()));
+        Page::new(batch, query.offset, query.limit)
+    }
+
+    /// Responds with players in the world
+    ///
+    /// Optionally filtered by address or by a box around a position
+    pub async fn players<W: WorldState<Player = P>, P: Player + Serialize>(
+        data: web::ThinData<W>,
+        query: web::Query<PlayersQuery>,
+    ) -> impl Responder
+    where
+        W::Id: Display,
+    {
+        let near = match (query.x, query.y) {
+            (Some(x), Some(y)) => Some((x, y, query.radius.unwrap_or(DEFAULT_RADIUS))),
+            (None, None) => None,
+            _ => {
+                let error = "x and y must be set together".to_string();
+                return Either::Right(ResponseAPI::error(error).with_code(StatusCode::BAD_REQUEST));
+            }
+        };
+
+        let mut players = data
+            .get_all_players()
+            .into_iter()
+            .map(|(id, player)| (id.to_string(), player))
+            .filter(|(id, _)| {
+                let address = query.address.as_deref();
+                address.is_none_or(|address| id.eq_ignore_ascii_case(address))
+            })
+            .filter(|(_, player)| {
+                let position = player.position();
+                near.is_none_or(|(x, y, radius)| {
+                    (position.x() - x).abs() <= radius && (position.y() - y).abs() <= radius
+                })
+            })
+            .collect::<Vec<_>>();
+        players.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let players = players.into_iter().map(|(_, player)| player).collect();
+        Either::Left(ResponseAPI::success(Page::new(
+            players,
+            query.offset,
+            query.limit,
+        )))
+    }
+
+    /// Responds with a single player
+    pub async fn player<W: WorldState<Player = P>, P: Player + Serialize>(
+        data: web::ThinData<W>,
+        address: web::Path<String>,
+    ) -> impl Responder
+    where
+        W::Id: Display,
+    {
+        let player = data
+            .get_all_players()
+            .into_iter()
+            .find(|(id, _)| id.to_string().eq_ignore_ascii_case(&address));
+
+        match player {
+            Some((_, player)) => Either::Left(ResponseAPI::success(player)),
+            None => Either::Right(
+                ResponseAPI::error("Player not found").with_code(StatusCode::NOT_FOUND),
+            ),
+        }
     }
 
     /// Responds with chat messages in the world
-    pub async fn chat_messages<W: WorldState<Message = M>, M: Serialize>(
+    ///
+    /// Optionally filtered by channel, sender and time
+    /// Decrypted direct messages are only listed to the node operator
+    pub async fn chat_messages<W: WorldState>(
+        authorized: Option<Authorized>,
         data: web::ThinData<W>,
-    ) -> impl Responder {
-        let messages = data.get_chat_messages();
-        ResponseAPI::success(messages)
+        query: web::Query<ChatQuery>,
+    ) -> impl Responder
+    where
+        W::Message: Serialize,
+    {
+        let messages = match &query.channel {
+            Some(channel) => data.get_channel_messages(channel),
+            None => data.get_chat_messages(),
+        };
+
+        let address = query.address.as_deref();
+        let messages = messages
+            .into_iter()
+            .filter(|message| {
+                authorized.is_some() || !matches!(message.scope(), ChatScope::Direct(_))
+            })
+            .filter(|message| matches(message, address, query.since))
+            .collect();
+        ResponseAPI::success(Page::new(messages, query.offset, query.limit))
+    }
+
+    /// Responds with the active peer and address bans
+    pub async fn bans<W: WorldState>(data: web::ThinData<W>) -> impl Responder {
+        let bans = data.get_bans();
+        ResponseAPI::success(bans)
+    }
+
+    /// Describes the world status endpoints
+    pub fn document<M, P, B>(doc: &mut ApiDoc)
+    where
+        M: JsonSchema,
+        P: JsonSchema,
+        B: JsonSchema,
+    {
+        doc.endpoint(Method::GET, "/mine", "Mined shares of the current batch")
+            .query::<MineQuery>()
+            .response::<Page<B>>(StatusCode::OK, "Page of mined shares");
+        doc.endpoint(
+            Method::GET,
+            "/mine/{address}",
+            "Shares and contribution of a miner",
+        )
+        .query::<MineQuery>()
+        .response::<MinerShares>(StatusCode::OK, "Contribution and page of shares");
+        doc.endpoint(Method::GET, "/players", "Players in the world")
+            .query::<PlayersQuery>()
+            .response::<Page<P>>(StatusCode::OK, "Page of players")
+            .error(StatusCode::BAD_REQUEST, "Invalid filters");
+        doc.endpoint(Method::GET, "/players/{address}", "Player details")
+            .response::<P>(StatusCode::OK, "The player")
+            .error(StatusCode::NOT_FOUND, "Unknown player");
+        doc.endpoint(Method::GET, "/chat", "Chat messages")
+            .query::<ChatQuery>()
+            .response::<Page<M>>(
+                StatusCode::OK,
+                "Page of chat messages, direct ones with the bearer token",
+            );
+        doc.endpoint(Method::GET, "/bans", "Active peer and address bans")
+            .response::<Vec<BanEntry>>(StatusCode::OK, "Active bans");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::TestWorld;
+    use actix_web::{App, test};
+
+    #[actix_web::test]
+    async fn test_miner_shares() {
+        let share = |miner: &str, timestamp: u64| MinedShare {
+            miner: miner.to_string(),
+            nonce: timestamp.to_string(),
+            block: false,
+            timestamp,
+        };
+        let world = TestWorld {
+            shares: vec![share("0xa", 1), share("0xa", 2), share("0xb", 3)],
+            ..Default::default()
+        };
+        let app = test::init_service(App::new().app_data(web::ThinData(world)).route(
+            "/mine/{address}",
+            web::get().to(WorldStatus::miner_shares::<TestWorld>),
+        ))
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/mine/0xa?since=1")
+            .to_request();
+        let response: ResponseAPI<MinerShares> = test::call_and_read_body_json(&app, request).await;
+        let shares = response.data;
+        assert_eq!(shares.miner, "0xa");
+        assert_eq!(shares.contribution, 2);
+        assert_eq!(shares.shares.total, 1);
+        assert_eq!(shares.shares.items[0].nonce, "2");
     }
 }
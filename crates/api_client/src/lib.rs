@@ -0,0 +1,273 @@
+use anyhow::{Result, anyhow};
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use game_api::{ResponseAPI, ResponseStatus};
+use game_primitives::admin::{MinerStatus, PeerInfo};
+use game_primitives::feed::FeedEvent;
+use game_primitives::health::WorldHealth;
+use game_primitives::message::ChatMessage;
+use game_primitives::reputation::BanEntry;
+use reqwest::{Method, RequestBuilder, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+pub use game_api::{
+    BanQuery, ChatQuery, ChatRequest, DialRequest, Direction, FeedQuery, LogRequest, MineQuery,
+    MinerRequest, MinerShares, MoveRequest, Page, PlayersQuery,
+};
+
+/// Api Client
+///
+/// Typed client of the node API
+/// Players and mined shares are decoded into the caller's types
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    http: reqwest::Client,
+    base: Url,
+    token: Option<String>,
+}
+
+impl ApiClient {
+    /// Creates a new client of the API at `base`, e.g. `http://localhost:8080`
+    pub fn new(base: &str) -> Result<Self> {
+        // Keep the base path when joining the endpoints
+        let base = match base.ends_with('/') {
+            true => Url::parse(base)?,
+            false => Url::parse(&format!("{base}/"))?,
+        };
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base,
+            token: None,
+        })
+    }
+
+    /// Sets the bearer token of the write endpoints
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Gets the version of the API server
+    pub async fn version(&self) -> Result<String> {
+        Self::send(self.request(Method::GET, "")?).await
+    }
+
+    /// Checks that the core loop of the node is running
+    pub async fn health(&self) -> Result<String> {
+        Self::send(self.request(Method::GET, "health")?).await
+    }
+
+    /// Gets the health of the node subsystems
+    ///
+    /// The report is returned even when the node is not ready
+    pub async fn status(&self) -> Result<WorldHealth> {
+        let response = self.request(Method::GET, "status")?.send().await?;
+        let response = match response.status() {
+            StatusCode::SERVICE_UNAVAILABLE => response,
+            _ => response.error_for_status()?,
+        };
+        Ok(response.json::<ResponseAPI<WorldHealth>>().await?.data)
+    }
+
+    /// Gets the OpenAPI document of the API
+    pub async fn openapi(&self) -> Result<serde_json::Value> {
+        let response = self.request(Method::GET, "openapi.json")?.send().await?;
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    /// Gets the Prometheus metrics of the node in the OpenMetrics text format
+    pub async fn metrics(&self) -> Result<String> {
+        let response = self.request(Method::GET, "metrics")?.send().await?;
+        Ok(response.error_for_status()?.text().await?)
+    }
+
+    /// Gets the mined shares of the current batch
+    pub async fn mined<B: DeserializeOwned>(&self, query: &MineQuery) -> Result<Page<B>> {
+        Self::send(self.request(Method::GET, "mine")?.query(query)).await
+    }
+
+    /// Gets the accounted work and shares of a miner
+    pub async fn miner_shares(&self, address: &str, query: &MineQuery) -> Result<MinerShares> {
+        let path = format!("mine/{address}");
+        Self::send(self.request(Method::GET, &path)?.query(query)).await
+    }
+
+    /// Gets the players in the world
+    pub async fn players<P: DeserializeOwned>(&self, query: &PlayersQuery) -> Result<Page<P>> {
+        Self::send(self.request(Method::GET, "players")?.query(query)).await
+    }
+
+    /// Gets a single player
+    pub async fn player<P: DeserializeOwned>(&self, address: &str) -> Result<P> {
+        let path = format!("players/{address}");
+        Self::send(self.request(Method::GET, &path)?).await
+    }
+
+    /// Gets the chat messages
+    pub async fn chat_messages(&self, query: &ChatQuery) -> Result<Page<ChatMessage>> {
+        Self::send(self.request(Method::GET, "chat")?.query(query)).await
+    }
+
+    /// Gets the active peer and address bans
+    pub async fn bans(&self) -> Result<Vec<BanEntry>> {
+        Self::send(self.request(Method::GET, "bans")?).await
+    }
+
+    /// Moves the local player by one step
+    pub async fn move_player(&self, direction: Direction) -> Result<()> {
+        let body = MoveRequest { direction };
+        let _: String = Self::send(self.request(Method::POST, "move")?.json(&body)).await?;
+        Ok(())
+    }
+
+    /// Sends a chat message as the local player
+    pub async fn chat(&self, request: &ChatRequest) -> Result<()> {
+        let _: String = Self::send(self.request(Method::POST, "chat")?.json(request)).await?;
+        Ok(())
+    }
+
+    /// Removes the local player from the world
+    pub async fn quit(&self) -> Result<()> {
+        let _: String = Self::send(self.request(Method::POST, "quit")?).await?;
+        Ok(())
+    }
+
+    /// Gets the settings of the local miner
+    pub async fn miner(&self) -> Result<MinerStatus> {
+        Self::send(self.request(Method::GET, "admin/miner")?).await
+    }
+
+    /// Starts or stops the local miner and sets the nonces it mines per loop
+    pub async fn configure_miner(&self, request: &MinerRequest) -> Result<MinerStatus> {
+        Self::send(self.request(Method::POST, "admin/miner")?.json(request)).await
+    }
+
+    /// Submits a claim of a full batch of pending shares right away
+    ///
+    /// Returns the number of shares claimed, fails with fewer than a batch
+    pub async fn claim(&self) -> Result<usize> {
+        Self::send(self.request(Method::POST, "admin/claim")?).await
+    }
+
+    /// Gets the connected peers
+    pub async fn peers(&self) -> Result<Vec<PeerInfo>> {
+        Self::send(self.request(Method::GET, "admin/peers")?).await
+    }
+
+    /// Bans a peer, for an hour unless `duration` is set in seconds
+    pub async fn ban_peer(&self, peer_id: &str, duration: Option<u64>) -> Result<()> {
+        let path = format!("admin/peers/{peer_id}/ban");
+        let query = BanQuery { duration };
+        Self::send(self.request(Method::POST, &path)?.query(&query)).await
+    }
+
+    /// Dials a peer by its multiaddr
+    pub async fn dial(&self, address: &str) -> Result<()> {
+        let body = DialRequest {
+            address: address.to_string(),
+        };
+        Self::send(self.request(Method::POST, "admin/dial")?.json(&body)).await
+    }
+
+    /// Replaces the log filter directives of the node
+    pub async fn set_log_filter(&self, filter: &str) -> Result<()> {
+        let body = LogRequest {
+            filter: filter.to_string(),
+        };
+        let _: String = Self::send(self.request(Method::PUT, "admin/log")?.json(&body)).await?;
+        Ok(())
+    }
+
+    /// Gets a snapshot of the world state
+    pub async fn snapshot(&self) -> Result<serde_json::Value> {
+        Self::send(self.request(Method::GET, "admin/snapshot")?).await
+    }
+
+    /// Follows the live world events
+    ///
+    /// Reads the Server-Sent Events feed, the stream ends when the server
+    /// drops the subscription and can be resumed with the last seen id
+    pub async fn events(&self, query: &FeedQuery) -> Result<BoxStream<'static, Result<FeedEvent>>> {
+        let response = self
+            .request(Method::GET, "events")?
+            .query(query)
+            .send()
+            .await?;
+        let bytes = response.error_for_status()?.bytes_stream();
+
+        let stream = futures_util::stream::unfold(
+            (bytes, Vec::new()),
+            |(mut bytes, mut buffer)| async move {
+                loop {
+                    // Frames end with a blank line
+                    if let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                        let frame = buffer.drain(..end + 2).collect::<Vec<_>>();
+                        match parse_frame(&frame) {
+                            Some(event) => return Some((event, (bytes, buffer))),
+                            None => continue,
+                        }
+                    }
+
+                    match bytes.next().await? {
+                        Ok(chunk) => buffer.extend_from_slice(&chunk),
+                        Err(e) => return Some((Err(e.into()), (bytes, buffer))),
+                    }
+                }
+            },
+        );
+
+        Ok(stream.boxed())
+    }
+
+    /// Builds a request to an endpoint
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let url = self.base.join(path)?;
+        let request = self.http.request(method, url);
+        Ok(match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        })
+    }
+
+    /// Sends a request and unwraps the `ResponseAPI` data
+    async fn send<D: DeserializeOwned>(request: RequestBuilder) -> Result<D> {
+        let response = request.send().await?;
+        let code = response.status();
+        let body = response.bytes().await?;
+
+        if !code.is_success() {
+            return match serde_json::from_slice::<ResponseAPI<String>>(&body) {
+                Ok(error) => Err(anyhow!("API error {code}: {}", error.data)),
+                Err(_) => Err(anyhow!("API error {code}")),
+            };
+        }
+
+        let response = serde_json::from_slice::<ResponseAPI<D>>(&body)?;
+        match response.status {
+            ResponseStatus::Success => Ok(response.data),
+            ResponseStatus::Error => Err(anyhow!("API error {code}")),
+        }
+    }
+}
+
+/// Parses a Server-Sent Events frame
+///
+/// Returns nothing for frames without data, such as comments
+fn parse_frame(frame: &[u8]) -> Option<Result<FeedEvent>> {
+    let frame = match std::str::from_utf8(frame) {
+        Ok(frame) => frame,
+        Err(e) => return Some(Err(e.into())),
+    };
+
+    let data = frame
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim_start)
+        .collect::<Vec<_>>();
+    if data.is_empty() {
+        return None;
+    }
+
+    Some(serde_json::from_str(&data.join("\n")).map_err(Into::into))
+}
@@ -0,0 +1,43 @@
+use game_core::prelude::*;
+use game_core::world::{B256, Bootnode, NetworkConfig};
+use logging::LogConfig;
+
+mod logging;
+
+/// Default log filter, overridden by `RUST_LOG`
+const DEFAULT_LOG_FILTER: &str = "game_core=info,game_network=info,game_api=info";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Keep the guard to flush the logs on exit
+    let (_, _guard) = LogConfig::from_env(DEFAULT_LOG_FILTER, "bootnode")?.init()?;
+
+    // Load node key
+    let node_key = load_node_key()?;
+
+    // Run network only
+    let config = NetworkConfig::from_env()?;
+    Bootnode::new(node_key.to_vec(), config)?.run().await
+}
+
+/// Default path to node key file
+const DEFAULT_NODE_KEY_PATH: &str = "./node.key";
+
+/// Loads node key from environment variable or default file.
+///
+/// Generates and stores a new key to keep the peer id stable
+fn load_node_key() -> Result<B256> {
+    let key = match std::env::var("NODE_KEY") {
+        Ok(k) => k,
+        Err(_) => match std::fs::read_to_string(DEFAULT_NODE_KEY_PATH) {
+            Ok(k) => k,
+            Err(_) => {
+                let new_key = B256::random().to_string();
+                std::fs::write(DEFAULT_NODE_KEY_PATH, &new_key)?;
+                new_key
+            }
+        },
+    };
+
+    Ok(key.trim().parse::<B256>()?)
+}
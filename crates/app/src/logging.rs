@@ -0,0 +1,166 @@
+use game_core::prelude::*;
+use game_core::world::LogFilter;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, fmt, reload};
+
+/// Log Format
+///
+/// Used to pick between human readable and machine readable logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Log Config
+///
+/// Configures the logs of a binary from the environment
+#[derive(Debug)]
+pub struct LogConfig {
+    /// Filter directives, `RUST_LOG` or the binary default
+    pub filter: String,
+    /// Format of the log lines
+    pub format: LogFormat,
+    /// Directory of the rotated log files, logs go to stdout when unset
+    pub dir: Option<PathBuf>,
+    /// Rotation period of the log files
+    pub rotation: Rotation,
+    /// Name of the binary, prefixing the log files and naming the traces
+    pub name: &'static str,
+    /// Whether the spans are exported to an OTLP collector
+    pub otlp: bool,
+}
+
+/// Log Guard
+///
+/// Flushes the log file and the pending spans once dropped
+/// Must be kept until exit
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+    tracer: Option<SdkTracerProvider>,
+}
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        if let Some(tracer) = self.tracer.take()
+            && let Err(e) = tracer.shutdown()
+        {
+            eprintln!("Failed to flush the pending spans: {e}");
+        }
+    }
+}
+
+impl LogConfig {
+    /// Reads the log config from the environment
+    ///
+    /// `RUST_LOG`, `LOG_FORMAT`, `LOG_DIR`, `LOG_ROTATION`, and the
+    /// standard `OTEL_EXPORTER_OTLP_ENDPOINT` enabling the span export
+    pub fn from_env(default_filter: &str, name: &'static str) -> Result<Self> {
+        let filter = std::env::var("RUST_LOG").unwrap_or_else(|_| default_filter.to_string());
+
+        let format = match std::env::var("LOG_FORMAT").as_deref() {
+            Ok("json") => LogFormat::Json,
+            Ok("text") | Err(_) => LogFormat::Text,
+            Ok(other) => return Err(anyhow!("Unknown log format {other}, expected text or json")),
+        };
+
+        let dir = std::env::var("LOG_DIR").ok().map(PathBuf::from);
+
+        let rotation = match std::env::var("LOG_ROTATION").as_deref() {
+            Ok("minutely") => Rotation::MINUTELY,
+            Ok("hourly") => Rotation::HOURLY,
+            Ok("daily") | Err(_) => Rotation::DAILY,
+            Ok("never") => Rotation::NEVER,
+            Ok(other) => {
+                return Err(anyhow!(
+                    "Unknown log rotation {other}, expected minutely, hourly, daily or never"
+                ));
+            }
+        };
+
+        let otlp = [
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+        ]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some());
+
+        Ok(Self {
+            filter,
+            format,
+            dir,
+            rotation,
+            name,
+            otlp,
+        })
+    }
+
+    /// Installs the global subscriber
+    ///
+    /// Returns the hook changing the filter at runtime, and the guard
+    /// flushing the logs that must be kept until exit
+    pub fn init(self) -> Result<(LogFilter, LogGuard)> {
+        let filter = EnvFilter::try_new(&self.filter)
+            .map_err(|e| anyhow!("Invalid log filter {}: {e}", self.filter))?;
+        let (filter, handle) = reload::Layer::new(filter);
+
+        // Write to the rotated files off the logging thread
+        let (writer, guard) = match &self.dir {
+            Some(dir) => {
+                let prefix = format!("{}.log", self.name);
+                let appender = RollingFileAppender::new(self.rotation, dir, prefix);
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                (BoxMakeWriter::new(writer), Some(guard))
+            }
+            None => (BoxMakeWriter::new(std::io::stdout), None),
+        };
+
+        let layer = fmt::layer()
+            .with_writer(writer)
+            .with_ansi(self.dir.is_none());
+        let layer = match self.format {
+            LogFormat::Text => layer.boxed(),
+            LogFormat::Json => layer.json().flatten_event(true).boxed(),
+        };
+
+        // Export the spans in batches, off the logging thread
+        let tracer = if self.otlp {
+            let exporter = SpanExporter::builder().with_http().build()?;
+            let resource = Resource::builder().with_service_name(self.name).build();
+            let provider = SdkTracerProvider::builder()
+                .with_batch_exporter(exporter)
+                .with_resource(resource)
+                .build();
+            Some(provider)
+        } else {
+            None
+        };
+        let otel = tracer
+            .as_ref()
+            .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(self.name)));
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(layer)
+            .with(otel)
+            .try_init()?;
+
+        let log_filter = LogFilter::new(move |directives| {
+            let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+            handle.reload(filter).map_err(|e| e.to_string())
+        });
+        let guard = LogGuard {
+            _file: guard,
+            tracer,
+        };
+        Ok((log_filter, guard))
+    }
+}
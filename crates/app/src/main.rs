@@ -1,21 +1,28 @@
 use game_core::prelude::*;
-use game_core::world::{B256, Character, Keypair, World};
+use game_core::world::{B256, Character, Keypair, TileMap, World};
+use logging::LogConfig;
+
+mod logging;
+
+/// Default log filter, overridden by `RUST_LOG`
+const DEFAULT_LOG_FILTER: &str = "game_core=debug,game_contract=debug,game_network=debug,game_app=debug,game_interface=debug,game_sprite=debug,game_api=debug";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::fmt()
-        .with_env_filter(
-            "game_core=debug,game_contract=debug,game_network=debug,game_app=debug,game_interface=debug,game_sprite=debug,game_api=debug",
-        )
-        .init();
+    // Keep the guard to flush the logs on exit
+    let (log_filter, _guard) = LogConfig::from_env(DEFAULT_LOG_FILTER, "app")?.init()?;
 
     // Load private key
     let private_key = load_private_key()?;
     let keypair = Keypair::from_slice(&*private_key)?;
 
+    // Load world map
+    let map = TileMap::load(DEFAULT_MAP_PATH)?;
+    let spawn = map.spawn_point(keypair.address().as_slice());
+
     // Initialize world
-    let character = Character::new(keypair.address(), 0, (0, 0));
-    let world = World::new(character);
+    let character = Character::new(keypair.address(), 0, (spawn.x, spawn.y));
+    let world = World::new(character, map).with_log_filter(log_filter);
     world.initialize(private_key.to_vec()).await?;
 
     Ok(())
@@ -23,6 +30,8 @@ async fn main() -> Result<()> {
 
 /// Default path to private key file
 const DEFAULT_KEY_PATH: &str = "./private.key";
+/// Default path to world map file
+const DEFAULT_MAP_PATH: &str = "./assets/maps/world.json";
 
 /// Loads private key from environment variable, file argument, or default file.
 fn load_private_key() -> Result<B256> {
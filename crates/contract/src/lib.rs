@@ -1,9 +1,11 @@
 pub mod ens;
 pub mod miner;
+pub mod vardiff;
 
 /// Common Types
 pub mod prelude {
     pub use alloy::primitives::{Address, B256, U256, address, keccak256};
+    pub use alloy::providers::{DynProvider, Provider};
     pub use alloy::signers::{Signature, Signer, local::LocalSigner};
     pub use tracing::{debug, error, info, trace, warn};
 }
@@ -66,6 +68,8 @@ impl RewarderClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vardiff::VarDiff;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn it_works() -> anyhow::Result<()> {
@@ -75,4 +79,32 @@ mod tests {
         println!("Reward Amount: {result}");
         Ok(())
     }
+
+    #[test]
+    fn test_vardiff_retarget() {
+        let difficulty = address!("0x000000ffffffffffffffffffffffffffffffffff");
+        let mut vardiff = VarDiff::new(difficulty);
+        let initial = vardiff.target();
+
+        // No shares makes the target easier
+        vardiff.adjust(Duration::from_secs(60));
+        assert!(vardiff.target() > initial);
+
+        // Too many shares makes the target harder
+        let easier = vardiff.target();
+        (0..100).for_each(|_| vardiff.record());
+        vardiff.adjust(Duration::from_secs(60));
+        assert!(vardiff.target() < easier);
+
+        // Target stays within bounds
+        for _ in 0..32 {
+            (0..1000).for_each(|_| vardiff.record());
+            vardiff.adjust(Duration::from_secs(60));
+        }
+        assert_eq!(vardiff.target(), difficulty);
+        for _ in 0..32 {
+            vardiff.adjust(Duration::from_secs(60));
+        }
+        assert_eq!(vardiff.target(), VarDiff::max_target(difficulty));
+    }
 }
@@ -1,5 +1,9 @@
 use crate::prelude::*;
+use crate::vardiff::{VarDiff, to_number};
 use alloy::{primitives::keccak256, sol, sol_types::SolValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 
 sol!(
     #[sol(rpc)]
@@ -7,6 +11,25 @@ sol!(
     "../../contracts/rewarder.json"
 );
 
+/// Share
+///
+/// Proof of work below the share target claimed by the miner
+/// Flagged as a block when it also passes the network difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Share {
+    pub address: Address,
+    pub nonce: U256,
+    pub target: Address,
+    pub block: bool,
+}
+
+impl Share {
+    /// Amount of work represented by the share
+    pub fn work(&self) -> U256 {
+        (U256::ONE << 160) / (to_number(self.target) + U256::ONE)
+    }
+}
+
 /// Miner
 ///
 /// Responsible for mining the nonce
@@ -17,9 +40,15 @@ pub struct Miner {
     pub(crate) salt: U256,
     pub(crate) init_hash: B256,
     pub(crate) difficulty: Address,
+    pub(crate) range: Range<U256>,
+    pub(crate) cursors: HashMap<U256, U256>,
+    pub(crate) vardiff: VarDiff,
 }
 
 impl Miner {
+    /// Backend name used in job announcements
+    pub const BACKEND: &str = "create2";
+
     /// Creates a new miner
     pub fn new(
         factory: Address,
@@ -29,6 +58,9 @@ impl Miner {
         difficulty: Address,
     ) -> Self {
         let salt = salt.try_into().unwrap_or_default();
+        let range = U256::ZERO..U256::MAX;
+        let cursors = Default::default();
+        let vardiff = VarDiff::new(difficulty);
 
         Self {
             factory,
@@ -36,37 +68,114 @@ impl Miner {
             salt,
             init_hash,
             difficulty,
+            range,
+            cursors,
+            vardiff,
+        }
+    }
+
+    /// Assigns a nonce range to the miner
+    ///
+    /// Resumes from the last nonce mined within the range
+    pub fn assign(&mut self, range: Range<U256>) -> &mut Self {
+        if self.range == range {
+            return self;
         }
+
+        // Save the progress of the current range
+        self.cursors.insert(self.range.start, self.salt);
+        self.salt = self
+            .cursors
+            .get(&range.start)
+            .copied()
+            .unwrap_or(range.start);
+        self.range = range;
+
+        self
+    }
+
+    /// Gets the current share target
+    pub fn share_target(&self) -> Address {
+        self.vardiff.target()
     }
 
     /// Mines a new address
-    fn mine(&self, address: Address, nonce: U256, init_hash: B256) -> anyhow::Result<()> {
+    fn mine(
+        &self,
+        address: Address,
+        nonce: U256,
+        init_hash: B256,
+        target: Address,
+    ) -> anyhow::Result<Address> {
         // keccak256(abi.encodePacked(nonce, minerAddress));
         let salt = keccak256((nonce, address).abi_encode_packed());
         let mined = self.factory.create2(salt, init_hash);
 
-        // Check against the network difficulty
-        if mined > self.difficulty {
-            return Err(anyhow::anyhow!("Not passed the network difficulty"));
+        // Check against the share target
+        if mined > target {
+            return Err(anyhow::anyhow!("Not passed the share target"));
         }
 
-        Ok(())
+        Ok(mined)
+    }
+
+    /// Checks if the assigned range is fully mined
+    pub fn is_exhausted(&self) -> bool {
+        self.salt + U256::ONE >= self.range.end
     }
 
     /// Run Miner
-    pub fn run(&mut self) -> anyhow::Result<(Address, U256)> {
+    pub fn run(&mut self) -> anyhow::Result<Share> {
+        // Stay within the assigned range
+        if self.is_exhausted() {
+            return Err(anyhow::anyhow!("Nonce range exhausted"));
+        }
+
+        // Adjust the share target to the hash rate
+        if let Some(target) = self.vardiff.retarget() {
+            debug!("Share target adjusted to: {target}");
+        }
+
         // Increment the nonce
         self.salt += U256::ONE;
 
-        // If mined, return the miner address and nonce
-        self.mine(self.address, self.salt, self.init_hash)?;
-        info!("Mined address: {} with salt: {}", self.address, self.salt);
+        // If mined, return the share
+        let target = self.vardiff.target();
+        let mined = self.mine(self.address, self.salt, self.init_hash, target)?;
+        self.vardiff.record();
+
+        let block = mined <= self.difficulty;
+        if block {
+            info!(address = %self.address, nonce = %self.salt, "Mined address");
+        }
+
+        Ok(Share {
+            address: self.address,
+            nonce: self.salt,
+            target,
+            block,
+        })
+    }
 
-        Ok((self.address, self.salt))
+    /// Job parameters of the backend
+    ///
+    /// Peers mining the same contract share the same parameters
+    pub fn params(&self) -> Vec<u8> {
+        (self.factory, self.init_hash, self.difficulty).abi_encode()
     }
 
-    /// Verify mined address
-    pub fn verify(&self, address: Address, nonce: U256) -> anyhow::Result<()> {
-        self.mine(address, nonce, self.init_hash)
+    /// Verify a share against the target it claims
+    pub fn verify(&self, share: &Share) -> anyhow::Result<()> {
+        if share.target > VarDiff::max_target(self.difficulty) {
+            return Err(anyhow::anyhow!("Share target is too easy"));
+        }
+
+        // Block flag must match the network difficulty
+        let mined = self.mine(share.address, share.nonce, self.init_hash, share.target)?;
+        if share.block != (mined <= self.difficulty) {
+            return Err(anyhow::anyhow!("Share block flag is invalid"));
+        }
+
+        Ok(())
     }
 }
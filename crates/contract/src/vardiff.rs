@@ -0,0 +1,96 @@
+use crate::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Variable Difficulty
+///
+/// Adjusts the share target of a miner to hit a steady share rate
+#[derive(Debug, Clone)]
+pub struct VarDiff {
+    pub(crate) target: Address,
+    pub(crate) difficulty: Address,
+    pub(crate) shares: u32,
+    pub(crate) window: Instant,
+}
+
+impl VarDiff {
+    /// Expected time between two shares
+    pub const SHARE_INTERVAL: Duration = Duration::from_secs(10);
+    /// Time between two target adjustments
+    pub const RETARGET_INTERVAL: Duration = Duration::from_secs(60);
+    /// Bits the share target can be easier than the network difficulty
+    pub const MAX_EASE_BITS: usize = 24;
+    /// Bits the initial share target is easier than the network difficulty
+    const INITIAL_EASE_BITS: usize = 8;
+    /// Maximum adjustment factor of a single retarget
+    const MAX_STEP: u64 = 4;
+
+    /// Creates a new variable difficulty for the network difficulty
+    pub fn new(difficulty: Address) -> Self {
+        let target = to_address(to_number(difficulty) << Self::INITIAL_EASE_BITS);
+
+        Self {
+            target: target.min(Self::max_target(difficulty)),
+            difficulty,
+            shares: 0,
+            window: Instant::now(),
+        }
+    }
+
+    /// Gets the current share target
+    pub fn target(&self) -> Address {
+        self.target
+    }
+
+    /// Easiest share target accepted for the network difficulty
+    pub fn max_target(difficulty: Address) -> Address {
+        let max = to_number(Address::repeat_byte(0xff));
+        to_address((to_number(difficulty) << Self::MAX_EASE_BITS).min(max))
+    }
+
+    /// Records a found share
+    pub fn record(&mut self) {
+        self.shares += 1;
+    }
+
+    /// Adjusts the target once the retarget window has elapsed
+    pub fn retarget(&mut self) -> Option<Address> {
+        let elapsed = self.window.elapsed();
+        if elapsed < Self::RETARGET_INTERVAL {
+            return None;
+        }
+
+        self.adjust(elapsed);
+        Some(self.target)
+    }
+
+    /// Scales the target by the ratio of expected and found shares
+    pub(crate) fn adjust(&mut self, elapsed: Duration) {
+        let expected = (elapsed.as_secs() / Self::SHARE_INTERVAL.as_secs()).max(1);
+        let found = self.shares as u64;
+        let target = to_number(self.target);
+
+        // Bound the step in both directions
+        let target = match found {
+            0 => target * U256::from(Self::MAX_STEP),
+            f if f > expected * Self::MAX_STEP => target / U256::from(Self::MAX_STEP),
+            f => target * U256::from(expected) / U256::from(f),
+        };
+
+        // Never harder than the network and never easier than the maximum
+        let min = to_number(self.difficulty);
+        let max = to_number(Self::max_target(self.difficulty));
+        self.target = to_address(target.clamp(min, max));
+        self.shares = 0;
+        self.window = Instant::now();
+    }
+}
+
+/// Converts an address into a number
+pub fn to_number(address: Address) -> U256 {
+    U256::from_be_slice(address.as_slice())
+}
+
+/// Converts a number into an address, keeping the lower bytes
+pub fn to_address(number: U256) -> Address {
+    Address::from_word(number.into())
+}
@@ -0,0 +1,98 @@
+use crate::channels::{SignableMessage, SignedMessage};
+use crate::map::CHAIN_ID;
+use crate::prelude::*;
+use crate::shutdown;
+use game_network::prelude::Keypair;
+use game_network::prelude::gossipsub::MessageAcceptance;
+use game_network::{NetworkCommand, NetworkConfig, Peer2Peer, Topical};
+use tracing::info_span;
+
+/// Default bind address of the metrics server
+#[cfg(feature = "api")]
+const METRICS_ADDR: &str = "0.0.0.0:9090";
+
+/// Bootnode
+///
+/// Headless node running only the network stack
+/// Serves Kademlia, relays circuits and forwards validly signed gossip
+pub struct Bootnode {
+    keypair: Keypair,
+    config: NetworkConfig,
+}
+
+impl Bootnode {
+    /// Creates a new bootnode from its node key
+    ///
+    /// The node key only sets the peer identity, no wallet is involved
+    pub fn new(node_key: Vec<u8>, mut config: NetworkConfig) -> Result<Self> {
+        let keypair = Keypair::ed25519_from_bytes(node_key)?;
+        config.kad_server = true;
+        config.relay_server = true;
+        if config.namespace.is_empty() {
+            config.namespace = CHAIN_ID.to_string();
+        }
+
+        Ok(Self { keypair, config })
+    }
+
+    /// Runs the bootnode until a shutdown signal
+    pub async fn run(self) -> Result<()> {
+        let network =
+            Peer2Peer::<SignedMessage<GameEventMessage>>::build(self.keypair, self.config)?;
+        let control = network.handle();
+
+        // Run metrics loop
+        #[cfg(feature = "api")]
+        {
+            let bind = std::env::var("METRICS_ADDR").unwrap_or_else(|_| METRICS_ADDR.to_string());
+            let server = game_api::MetricsServer::new(network.metrics(), bind).bind()?;
+            tokio::spawn(server);
+        }
+
+        let (_tx, mut rx) = network.start();
+        let signal = shutdown::signal();
+        tokio::pin!(signal);
+        loop {
+            tokio::select! {
+                result = &mut signal => break result?,
+                received = rx.recv() => {
+                    let Some(received) = received else {
+                        return Err(anyhow!("Network stopped"));
+                    };
+
+                    // Only forward messages signed by their author, on their own topic, from their own peer
+                    let acceptance = match SignedMessage::<GameEventMessage>::try_from(&received.message) {
+                        Ok(signed) => {
+                            // Continue the trace of the sender through the relay
+                            let span = signed.traced(info_span!(
+                                "relay",
+                                peer_id = %received.propagation_source,
+                                event_type = signed.data.name()
+                            ));
+                            let _entered = span.enter();
+                            let publisher = received.message.source;
+                            if signed.verify().is_ok()
+                                && received.kind == Some(signed.topic_kind())
+                                && publisher.is_some_and(|peer| signed.is_published_by(&peer))
+                            {
+                                MessageAcceptance::Accept
+                            } else {
+                                debug!("Rejected relayed message");
+                                MessageAcceptance::Reject
+                            }
+                        }
+                        Err(_) => MessageAcceptance::Reject,
+                    };
+                    let command = NetworkCommand::Validate(received.id, received.propagation_source, acceptance);
+                    control.send(command).await?;
+                }
+            }
+        }
+
+        // Close the connections
+        control.shutdown().await?;
+
+        info!("Bootnode stopped");
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
 use crate::BincodeHelper;
-use crate::prelude::{Deserialize, GameEventMessage, Serialize, error};
+use crate::prelude::{Deserialize, GameEvent, GameEventMessage, Serialize, error};
+use crate::telemetry::TraceContext;
 use async_trait::async_trait;
 use game_contract::prelude::{Address, Signature, Signer, keccak256};
+use game_network::prelude::PeerId;
 use game_network::prelude::gossipsub::Message as GossipMessage;
+use game_network::{TopicKind, Topical, Traceable};
+use k256::ecdsa::VerifyingKey;
+use tracing::{Span, info_span};
 
 /// Signed Message
 ///
@@ -14,6 +19,9 @@ pub trait SignableMessage {
     fn signature(&self) -> Signature;
     fn signature_mut(&mut self) -> &mut Signature;
 
+    /// Carries the span context of the sender, left out of the signature
+    fn set_trace(&mut self, _trace: TraceContext) {}
+
     /// Verifies the signature of the message using the provided address
     fn verify(&self) -> anyhow::Result<()> {
         let hash = keccak256(self.encoded_data()?);
@@ -28,6 +36,12 @@ pub trait SignableMessage {
         Ok(())
     }
 
+    /// Recovers the public key of the signer
+    fn recover_key(&self) -> anyhow::Result<VerifyingKey> {
+        let hash = keccak256(self.encoded_data()?);
+        Ok(self.signature().recover_from_prehash(&hash)?)
+    }
+
     /// Signs the message using the provided signer
     async fn sign<S: Signer + Send + Sync>(&mut self, signer: &S) -> anyhow::Result<()> {
         let hash = keccak256(self.encoded_data()?);
@@ -37,11 +51,17 @@ pub trait SignableMessage {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedMessage<D: Serialize> {
     pub data: D,
     pub address: Address,
+    /// Peer allowed to publish the message, empty for local messages
+    pub publisher: Vec<u8>,
+    /// Increasing number of the message among the ones of the address
+    pub sequence: u64,
     pub signature: Signature,
+    /// Span context of the sender, only used to follow the message
+    pub trace: Option<TraceContext>,
 }
 
 impl<D: Serialize> SignedMessage<D> {
@@ -51,14 +71,37 @@ impl<D: Serialize> SignedMessage<D> {
         Self {
             data,
             address,
+            publisher: Vec::new(),
+            sequence: 0,
             signature,
+            trace: None,
+        }
+    }
+
+    /// Binds the message to the peer publishing it, before signing
+    pub fn published_by(mut self, peer: &PeerId, sequence: u64) -> Self {
+        self.publisher = peer.to_bytes();
+        self.sequence = sequence;
+        self
+    }
+
+    /// Checks that the message was published by the peer it is bound to
+    pub fn is_published_by(&self, peer: &PeerId) -> bool {
+        self.publisher == peer.to_bytes()
+    }
+
+    /// Makes a span continue the trace of the sender
+    pub fn traced(&self, span: Span) -> Span {
+        if let Some(trace) = &self.trace {
+            trace.attach(&span);
         }
+        span
     }
 }
 
 impl<D: Serialize> SignableMessage for SignedMessage<D> {
     fn encoded_data(&self) -> anyhow::Result<Vec<u8>> {
-        let packed = (&self.data, &self.address);
+        let packed = (&self.data, &self.address, &self.publisher, self.sequence);
         BincodeHelper::encode(&packed)
     }
 
@@ -73,6 +116,10 @@ impl<D: Serialize> SignableMessage for SignedMessage<D> {
     fn signature_mut(&mut self) -> &mut Signature {
         &mut self.signature
     }
+
+    fn set_trace(&mut self, trace: TraceContext) {
+        self.trace = Some(trace);
+    }
 }
 
 impl SignableMessage for GossipMessage {
@@ -106,6 +153,26 @@ impl<D: for<'de> Deserialize<'de> + Serialize> TryFrom<&GossipMessage> for Signe
     }
 }
 
+impl Topical for SignedMessage<GameEventMessage> {
+    fn topic_kind(&self) -> TopicKind {
+        match self.data {
+            GameEvent::PlayerMovement(_) => TopicKind::Movement,
+            GameEvent::ChatMessage(_)
+            | GameEvent::ChannelMessage(..)
+            | GameEvent::Whisper(..)
+            | GameEvent::DirectMessage(_) => TopicKind::Chat,
+            GameEvent::PlayerFound(_) | GameEvent::Work(_) => TopicKind::Shares,
+            GameEvent::Quit => TopicKind::Presence,
+        }
+    }
+}
+
+impl Traceable for SignedMessage<GameEventMessage> {
+    fn span(&self) -> Span {
+        self.traced(info_span!("publish", event_type = self.data.name()))
+    }
+}
+
 impl<T: Serialize> From<SignedMessage<T>> for Vec<u8> {
     fn from(val: SignedMessage<T>) -> Self {
         BincodeHelper::encode(&val).unwrap()
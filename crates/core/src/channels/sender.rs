@@ -1,23 +1,29 @@
 use crate::channels::SignableMessage;
+use crate::telemetry::TraceContext;
 use async_trait::async_trait;
 use game_contract::prelude::Signer;
+use tracing::instrument;
 
 /// Signed Sender
 ///
 /// Used to send signed messages to a channel
-/// Signs the message before sending it
+/// Signs the message before sending it, along with the current span context
 #[async_trait]
 pub trait SignedSender: Sender
 where
     Self::Message: SignableMessage + Send,
 {
     /// Send a signed message to the channel
+    #[instrument(skip_all)]
     async fn send_signed<S: Signer + Send + Sync>(
         &self,
         mut message: Self::Message,
         signer: &S,
     ) -> anyhow::Result<()> {
         message.sign(signer).await?;
+        if let Some(trace) = TraceContext::current() {
+            message.set_trace(trace);
+        }
         self.send(message).await?;
 
         Ok(())
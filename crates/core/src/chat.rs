@@ -0,0 +1,204 @@
+use crate::BincodeHelper;
+use crate::prelude::*;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use game_contract::prelude::keccak256;
+use game_primitives::message::{ChatMessage, ChatText, SealedMessage};
+use k256::PublicKey;
+use k256::ecdh::{EphemeralSecret, SharedSecret, diffie_hellman};
+use k256::ecdsa::{SigningKey, VerifyingKey};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// Default path to the persisted chat history
+pub const CHAT_PATH: &str = "./chat.json";
+/// Chat messages kept in the history
+pub const HISTORY_SIZE: usize = 256;
+/// Tolerated clock drift of chat timestamps in milliseconds
+pub const MAX_CLOCK_SKEW: u64 = 60 * 1000;
+/// Age after which chat messages are stale in milliseconds
+pub const MAX_MESSAGE_AGE: u64 = 10 * 60 * 1000;
+
+/// Key derivation context of sealed messages
+const SEAL_INFO: &[u8] = b"fonketh/whisper/1";
+/// Length of the cipher nonce
+const NONCE_LEN: usize = 12;
+/// Length of the cipher authentication tag
+pub const TAG_LEN: usize = 16;
+
+/// Derives the id of a chat message from its sender and signed payload
+pub fn message_id(sender: &Address, text: &ChatText) -> Result<String> {
+    let encoded = BincodeHelper::encode(&(sender, text))?;
+    Ok(keccak256(encoded).to_string())
+}
+
+/// Seals a message for its recipient
+///
+/// Encrypts with a key agreed between an ephemeral key and the recipient's key
+pub fn seal(recipient: Address, key: &VerifyingKey, text: &ChatText) -> Result<SealedMessage> {
+    let secret = EphemeralSecret::random(&mut OsRng);
+    let ephemeral = secret.public_key().to_sec1_bytes().to_vec();
+    let shared = secret.diffie_hellman(&PublicKey::from(key));
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(&shared, &ephemeral)?
+        .encrypt(&nonce, BincodeHelper::encode(text)?.as_slice())
+        .map_err(|e| anyhow!("Failed to seal message: {e}"))?;
+
+    Ok(SealedMessage {
+        recipient: recipient.to_vec(),
+        ephemeral,
+        nonce: nonce.to_vec(),
+        ciphertext,
+    })
+}
+
+/// Opens a message sealed for the key owner
+pub fn open(key: &SigningKey, sealed: &SealedMessage) -> Result<ChatText> {
+    if sealed.nonce.len() != NONCE_LEN {
+        return Err(anyhow!("Invalid nonce length {}", sealed.nonce.len()));
+    }
+
+    let ephemeral = PublicKey::from_sec1_bytes(&sealed.ephemeral)?;
+    let shared = diffie_hellman(key.as_nonzero_scalar(), ephemeral.as_affine());
+    let message = cipher(&shared, &sealed.ephemeral)?
+        .decrypt(
+            Nonce::from_slice(&sealed.nonce),
+            sealed.ciphertext.as_slice(),
+        )
+        .map_err(|e| anyhow!("Failed to open message: {e}"))?;
+
+    BincodeHelper::decode(&message)
+}
+
+/// Derives the cipher from the shared secret
+fn cipher(shared: &SharedSecret, ephemeral: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    shared
+        .extract::<Sha256>(Some(ephemeral))
+        .expand(SEAL_INFO, &mut key)
+        .map_err(|e| anyhow!("Failed to derive key: {e}"))?;
+
+    Ok(Aes256Gcm::new(&key.into()))
+}
+
+/// Chat History
+///
+/// Bounded history of the chat messages and their senders
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "K: Serialize", deserialize = "K: Deserialize<'de>"))]
+pub struct ChatHistory<K> {
+    messages: VecDeque<(K, ChatMessage)>,
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl<K> Default for ChatHistory<K> {
+    fn default() -> Self {
+        let messages = Default::default();
+        Self {
+            messages,
+            changed: false,
+        }
+    }
+}
+
+impl<K> ChatHistory<K>
+where
+    K: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Creates a new chat history
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Checks if a message is in the history
+    pub fn contains(&self, id: &str) -> bool {
+        self.messages.iter().any(|(_, m)| m.id == id)
+    }
+
+    /// Adds a message, dropping the oldest once full
+    ///
+    /// Returns false if the message is a duplicate
+    pub fn push(&mut self, sender: K, message: ChatMessage) -> bool {
+        if self.contains(&message.id) {
+            return false;
+        }
+
+        if self.messages.len() >= HISTORY_SIZE {
+            self.messages.pop_front();
+        }
+        self.messages.push_back((sender, message));
+        self.changed = true;
+        true
+    }
+
+    /// Gets the messages with their sender, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &(K, ChatMessage)> {
+        self.messages.iter()
+    }
+
+    /// Saves the history if it changed
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        if !std::mem::take(&mut self.changed) {
+            return Ok(());
+        }
+
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Loads the history
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_primitives::message::ChatScope;
+
+    #[test]
+    fn test_sealed_message() -> Result<()> {
+        let key = SigningKey::random(&mut OsRng);
+        let text = ChatText::new("gm".to_string());
+        let sealed = seal(Address::ZERO, key.verifying_key(), &text)?;
+        assert_eq!(sealed.recipient, Address::ZERO.to_vec());
+        assert_eq!(open(&key, &sealed)?, text);
+
+        // Other keys cannot open it
+        let other = SigningKey::random(&mut OsRng);
+        assert!(open(&other, &sealed).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_chat_history() -> Result<()> {
+        let mut history = ChatHistory::new();
+        let (sender, text) = (Address::ZERO, ChatText::new("gm".to_string()));
+        let id = message_id(&sender, &text)?;
+        let message = |id: String| {
+            let scope = ChatScope::Global;
+            ChatMessage::new(id, sender.to_string(), "zero".into(), text.clone(), scope)
+        };
+
+        // Duplicates are dropped
+        assert!(history.push(sender, message(id.clone())));
+        assert!(!history.push(sender, message(id.clone())));
+
+        // Oldest messages are dropped once full
+        for i in 0..HISTORY_SIZE {
+            history.push(sender, message(i.to_string()));
+        }
+        assert!(!history.contains(&id));
+        assert_eq!(history.iter().count(), HISTORY_SIZE);
+
+        // Round trips through serde
+        let encoded = serde_json::to_string(&history)?;
+        let decoded: ChatHistory<Address> = serde_json::from_str(&encoded)?;
+        assert_eq!(decoded.iter().last(), history.iter().last());
+        Ok(())
+    }
+}
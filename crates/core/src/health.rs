@@ -0,0 +1,212 @@
+use game_primitives::health::{
+    CheckStatus, ClaimsHealth, MinerHealth, NetworkHealth, RpcHealth, RunnerHealth, WorldHealth,
+};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Interval between the probes of the RPC and the peers
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(10);
+/// Time without a runner loop before the runner is considered dead
+pub const RUNNER_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time without a successful RPC request before the RPC is considered down
+pub const RPC_TIMEOUT: Duration = Duration::from_secs(60);
+/// Number of pending shares beyond which the claims are considered stuck
+pub const CLAIM_BACKLOG: usize = 100;
+
+/// Last outcome of the RPC probes
+#[derive(Debug, Default)]
+struct RpcProbe {
+    block: Option<u64>,
+    seen: Option<Instant>,
+    error: Option<String>,
+}
+
+/// Health Monitor
+///
+/// Tracks the liveness of the world subsystems
+/// Fed by the runner and the probes, read by the health checks
+#[derive(Debug)]
+pub struct HealthMonitor {
+    started: Instant,
+    tick: RwLock<Instant>,
+    gossip: RwLock<Option<Instant>>,
+    rpc: RwLock<RpcProbe>,
+    peers: AtomicUsize,
+    mining: AtomicBool,
+    pending: AtomicUsize,
+    in_flight: AtomicUsize,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthMonitor {
+    /// Creates a new monitor, the RPC is down until probed
+    pub fn new() -> Self {
+        let started = Instant::now();
+        Self {
+            started,
+            tick: RwLock::new(started),
+            gossip: Default::default(),
+            rpc: Default::default(),
+            peers: Default::default(),
+            mining: Default::default(),
+            pending: Default::default(),
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Records an iteration of the runner loop
+    pub fn tick(&self) {
+        *self.tick.write().unwrap() = Instant::now();
+    }
+
+    /// Records a gossip message received from the network
+    pub fn record_gossip(&self) {
+        *self.gossip.write().unwrap() = Some(Instant::now());
+    }
+
+    /// Records the outcome of an RPC probe
+    pub fn record_rpc(&self, block: Result<u64, String>) {
+        let mut rpc = self.rpc.write().unwrap();
+        match block {
+            Ok(block) => {
+                rpc.block = Some(block);
+                rpc.seen = Some(Instant::now());
+                rpc.error = None;
+            }
+            Err(e) => rpc.error = Some(e),
+        }
+    }
+
+    /// Sets the number of connected peers
+    pub fn set_peers(&self, peers: usize) {
+        self.peers.store(peers, Ordering::Relaxed);
+    }
+
+    /// Sets whether a nonce range is being mined
+    pub fn set_mining(&self, mining: bool) {
+        self.mining.store(mining, Ordering::Relaxed);
+    }
+
+    /// Sets the pending shares and the claim transactions in flight
+    pub fn set_claims(&self, pending: usize, in_flight: usize) {
+        self.pending.store(pending, Ordering::Relaxed);
+        self.in_flight.store(in_flight, Ordering::Relaxed);
+    }
+
+    /// Reports the health of the subsystems
+    pub fn report(&self) -> WorldHealth {
+        self.report_at(Instant::now())
+    }
+
+    /// Reports the health of the subsystems as of `now`
+    fn report_at(&self, now: Instant) -> WorldHealth {
+        let since = |instant: Instant| now.saturating_duration_since(instant);
+        let millis = |duration: Duration| duration.as_millis() as u64;
+
+        let last_tick = since(*self.tick.read().unwrap());
+        let runner = RunnerHealth {
+            status: if last_tick > RUNNER_TIMEOUT {
+                CheckStatus::Down
+            } else {
+                CheckStatus::Up
+            },
+            last_tick: millis(last_tick),
+        };
+
+        let probe = self.rpc.read().unwrap();
+        let last_seen = probe.seen.map(since);
+        let rpc = RpcHealth {
+            status: match last_seen {
+                Some(seen) if seen <= RPC_TIMEOUT && probe.error.is_none() => CheckStatus::Up,
+                Some(seen) if seen <= RPC_TIMEOUT => CheckStatus::Degraded,
+                _ => CheckStatus::Down,
+            },
+            block: probe.block,
+            last_seen: last_seen.map(millis),
+            error: probe.error.clone(),
+        };
+
+        let peers = self.peers.load(Ordering::Relaxed);
+        let network = NetworkHealth {
+            status: match peers {
+                0 => CheckStatus::Degraded,
+                _ => CheckStatus::Up,
+            },
+            peers,
+            last_gossip: self.gossip.read().unwrap().map(since).map(millis),
+        };
+
+        // Nodes built without mining have nothing to run
+        let enabled = cfg!(feature = "mine");
+        let running = self.mining.load(Ordering::Relaxed);
+        let miner = MinerHealth {
+            status: if enabled && !running {
+                CheckStatus::Degraded
+            } else {
+                CheckStatus::Up
+            },
+            enabled,
+            running,
+        };
+
+        let pending = self.pending.load(Ordering::Relaxed);
+        let claims = ClaimsHealth {
+            status: if pending >= CLAIM_BACKLOG {
+                CheckStatus::Degraded
+            } else {
+                CheckStatus::Up
+            },
+            pending,
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+        };
+
+        let uptime = millis(since(self.started));
+        WorldHealth::new(uptime, runner, rpc, network, miner, claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_needs_the_rpc() {
+        let monitor = HealthMonitor::new();
+        monitor.tick();
+        assert!(monitor.report().is_alive());
+        assert!(!monitor.report().is_ready());
+
+        monitor.record_rpc(Ok(42));
+        let health = monitor.report();
+        assert!(health.is_ready());
+        assert_eq!(health.rpc.block, Some(42));
+
+        // A failed probe keeps the last block until the RPC times out
+        monitor.record_rpc(Err("timeout".into()));
+        let health = monitor.report();
+        assert_eq!(health.rpc.status, CheckStatus::Degraded);
+        assert!(health.is_ready());
+
+        let later = Instant::now() + RPC_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(monitor.report_at(later).rpc.status, CheckStatus::Down);
+        assert!(!monitor.report_at(later).is_ready());
+    }
+
+    #[test]
+    fn test_stalled_runner_is_not_alive() {
+        let monitor = HealthMonitor::new();
+        monitor.record_rpc(Ok(1));
+        monitor.set_peers(3);
+
+        let later = Instant::now() + RUNNER_TIMEOUT + Duration::from_secs(1);
+        let health = monitor.report_at(later);
+        assert!(!health.is_alive());
+        assert_eq!(health.status, CheckStatus::Down);
+    }
+}
@@ -1,23 +1,39 @@
+pub mod bootnode;
 pub mod channels;
+pub mod chat;
+pub mod health;
 pub mod map;
+pub mod metrics;
+pub mod moderation;
 pub mod movements;
 pub mod player;
+pub mod reputation;
+pub mod shutdown;
+pub mod telemetry;
+pub mod tilemap;
+pub mod work;
 
 // Crate Internal API
 pub mod world {
+    pub use crate::bootnode::Bootnode;
     pub use crate::map::World;
     pub use crate::movements::Position;
     pub use crate::player::Character;
+    pub use crate::tilemap::TileMap;
     pub use game_contract::prelude::B256;
     pub use game_contract::prelude::LocalSigner as Keypair;
+    pub use game_network::NetworkConfig;
+    pub use game_primitives::admin::LogFilter;
     pub use game_primitives::events::GameEvent;
 }
 
 // Crate Prelude
 pub mod prelude {
-    pub type GameEventMessage = GameEvent<(Address, U256), Position>;
+    pub type GameEventMessage = GameEvent<Share, Position, WorkEvent>;
+    pub use crate::work::WorkEvent;
     pub use crate::world::Position;
     pub use anyhow::{Result, anyhow};
+    pub use game_contract::miner::Share;
     pub use game_contract::prelude::{Address, U256};
     pub use game_primitives::events::GameEvent;
     pub use serde::{Deserialize, Serialize};
@@ -1,19 +1,66 @@
-use crate::channels::{SignedMessage, SignedReceiver, SignedSender};
+use crate::channels::{Receiver, SignableMessage, SignedMessage, SignedSender};
+use crate::chat::{self, CHAT_PATH, ChatHistory, MAX_CLOCK_SKEW, MAX_MESSAGE_AGE};
+use crate::health::{HealthMonitor, PROBE_INTERVAL};
+use crate::metrics::WorldMetrics;
+use crate::moderation::{ChatFilter, MODERATION_PATH, Moderation};
 use crate::prelude::*;
-use crate::world::Character;
+use crate::reputation::{BANS_PATH, Offense, Reputation};
+use crate::shutdown;
+#[cfg(feature = "mine")]
+use crate::telemetry::TraceContext;
+use crate::tilemap::Zone;
+use crate::work::{CHUNK_BITS, Job, ShareLog, WorkPool, unix_time};
+use crate::world::{Character, TileMap};
 use game_contract::RewarderClient;
-use game_contract::miner::Rewarder;
-use game_network::Peer2Peer;
-use game_network::prelude::Keypair;
-use game_network::prelude::gossipsub::Message;
-use game_primitives::message::ChatMessage;
-use game_primitives::{ExitStatus, Identifier, WorldState};
+use game_contract::miner::{Miner, Rewarder};
+use game_contract::prelude::{DynProvider, Provider};
+use game_network::prelude::gossipsub::MessageAcceptance;
+use game_network::prelude::{Keypair, PeerId};
+use game_network::{
+    NetworkCommand, NetworkConfig, NetworkHandle, Peer2Peer, ReceivedMessage, TopicKind, Topical,
+};
+#[cfg(any(feature = "mine", feature = "api"))]
+use game_primitives::admin::MinerStatus;
+#[cfg(any(feature = "interface", feature = "api"))]
+use game_primitives::events::Traced;
+#[cfg(feature = "mine")]
+use game_primitives::feed::ClaimStatus;
+use game_primitives::feed::{EventFeed, WorldEvent};
+use game_primitives::health::WorldHealth;
+use game_primitives::map::{MinedShare, ZoneStatus};
+use game_primitives::message::{
+    ChatMessage, ChatScope, ChatText, MAX_CHANNEL_LENGTH, MAX_MESSAGE_LENGTH, unix_millis,
+};
+use game_primitives::reputation::BanEntry;
+use game_primitives::{ExitStatus, Identifier, Record, WorldState};
+use k256::ecdsa::VerifyingKey;
+use schemars::JsonSchema;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::hash::Hash;
-#[cfg(feature = "interface")]
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(any(feature = "interface", feature = "api"))]
 use std::sync::mpsc;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tracing::{Instrument, info_span, instrument};
+#[cfg(feature = "mine")]
+use tracing::{Span, field};
+#[cfg(feature = "api")]
+use {
+    crate::reputation::BAN_DURATION,
+    game_network::prelude::Multiaddr,
+    game_primitives::admin::{AdminCommand, LogFilter, MAX_WORKERS, PeerInfo},
+    std::time::Duration,
+};
+
+/// Chain of the rewarder contract
+pub(crate) const CHAIN_ID: u64 = 8453;
+/// Number of mined blocks submitted per claim transaction
+const CLAIM_BATCH: usize = 10;
+/// Admin commands buffered for the runner
+#[cfg(feature = "api")]
+const ADMIN_CAPACITY: usize = 16;
 
 /// Players pool
 ///
@@ -43,6 +90,12 @@ impl<I: Eq + Hash, B, T> PlayersPool<I, B, T> {
         players.remove(identifier);
     }
 
+    /// Checks if a player is in the players pool
+    pub fn contains_player(&self, identifier: &I) -> bool {
+        let players = self.players.read().unwrap();
+        players.contains_key(identifier)
+    }
+
     /// Updates a player in the players pool
     pub fn update_player<F, R>(&self, identifier: &I, func: F) -> Option<R>
     where
@@ -59,24 +112,74 @@ pub struct World<I, B, T = i32> {
     identifier: I,
     players: Arc<PlayersPool<I, B, T>>,
     mined: Arc<RwLock<HashSet<MinedBlock>>>,
-    messages: Arc<RwLock<Vec<ChatMessage>>>,
+    messages: Arc<RwLock<ChatHistory<I>>>,
     ens_cache: Arc<RwLock<HashMap<Address, String>>>,
+    #[serde(skip)]
+    map: Arc<TileMap>,
+    zone_shares: Arc<RwLock<HashMap<usize, u64>>>,
+    contributions: Arc<RwLock<HashMap<Address, u64>>>,
+    #[serde(skip)]
+    work: Arc<RwLock<WorkPool>>,
+    #[serde(skip)]
+    shares: Arc<RwLock<ShareLog>>,
+    #[serde(skip)]
+    reputation: Arc<RwLock<Reputation>>,
+    #[serde(skip)]
+    sequence: Arc<AtomicU64>,
+    #[serde(skip)]
+    keys: Arc<RwLock<HashMap<Address, VerifyingKey>>>,
+    #[serde(skip)]
+    moderation: Arc<RwLock<Moderation<I>>>,
+    #[serde(skip)]
+    feed: Arc<EventFeed>,
+    #[serde(skip)]
+    metrics: Arc<WorldMetrics>,
+    #[serde(skip)]
+    health: Arc<HealthMonitor>,
+    #[cfg(any(feature = "mine", feature = "api"))]
+    #[serde(skip)]
+    miner: Arc<RwLock<MinerStatus>>,
+    #[cfg(feature = "mine")]
+    #[serde(skip)]
+    block_traces: Arc<RwLock<Vec<TraceContext>>>,
+    #[cfg(feature = "api")]
+    #[serde(skip)]
+    log_filter: Option<LogFilter>,
 }
 
 impl<B> World<Address, B, i32>
 where
-    B: Clone + Eq + Hash + Send + Sync + Serialize + Default + 'static,
+    B: Clone + Eq + Hash + Send + Sync + Serialize + JsonSchema + Default + 'static,
 {
     /// Creates a new world
     ///
-    /// Initializes the world with the player
-    pub fn new(player: Character<Address, B, i32>) -> Self {
+    /// Initializes the world with the player and the map
+    pub fn new(player: Character<Address, B, i32>, map: TileMap) -> Self {
         let exit_status = Arc::new(ExitStatus::default());
         let players = Arc::new(PlayersPool::new());
         let identifier = player.identifier();
         let mined = Arc::new(Default::default());
-        let messages = Arc::new(Default::default());
+        let messages = Arc::new(RwLock::new(ChatHistory::new()));
         let ens_cache = Arc::new(Default::default());
+        let map = Arc::new(map);
+        let zone_shares = Arc::new(Default::default());
+        let contributions = Arc::new(Default::default());
+        let work = Arc::new(RwLock::new(WorkPool::new()));
+        let shares = Arc::new(RwLock::new(ShareLog::new()));
+        let reputation = Arc::new(RwLock::new(Reputation::new()));
+        // Starts from the clock so the sequence keeps increasing across restarts
+        let sequence = Arc::new(AtomicU64::new(unix_millis().saturating_mul(1000)));
+        let keys = Arc::new(Default::default());
+        let moderation = Arc::new(RwLock::new(Moderation::new()));
+        let feed = Arc::new(EventFeed::new());
+        let metrics = Arc::new(WorldMetrics::new());
+        let health = Arc::new(HealthMonitor::new());
+        #[cfg(any(feature = "mine", feature = "api"))]
+        let miner = Arc::new(RwLock::new(MinerStatus {
+            enabled: cfg!(feature = "mine"),
+            workers: 1,
+            pending: 0,
+        }));
 
         // Add player to players pool
         players.add_player(player.identifier(), player);
@@ -88,125 +191,878 @@ where
             mined,
             messages,
             ens_cache,
+            map,
+            zone_shares,
+            contributions,
+            work,
+            shares,
+            reputation,
+            sequence,
+            keys,
+            moderation,
+            feed,
+            metrics,
+            health,
+            #[cfg(any(feature = "mine", feature = "api"))]
+            miner,
+            #[cfg(feature = "mine")]
+            block_traces: Default::default(),
+            #[cfg(feature = "api")]
+            log_filter: None,
         }
     }
 
+    /// Sets a hook filtering the chat messages
+    pub fn with_chat_filter(self, filter: ChatFilter) -> Self {
+        self.moderation.write().unwrap().set_filter(filter);
+        self
+    }
+
+    /// Sets a hook changing the log filter from the admin api
+    #[cfg(feature = "api")]
+    pub fn with_log_filter(mut self, filter: LogFilter) -> Self {
+        self.log_filter = Some(filter);
+        self
+    }
+
     /// Initializes the world
     ///
-    /// Runs Network and Interface
+    /// Runs Network and Interface until a shutdown is requested
     pub async fn initialize(self, private_key: Vec<u8>) -> Result<()> {
         info!("Initializing world");
         let rpc = "https://mainnet.base.org";
-        let chain_id = 8453;
-        let client = RewarderClient::new(rpc, &private_key, chain_id).await?;
+        let client = RewarderClient::new(rpc, &private_key, CHAIN_ID).await?;
 
-        // Run network loop
+        // Local input from the interface and the api
+        #[cfg(any(feature = "interface", feature = "api"))]
+        let (txb, rxb) = mpsc::channel();
+
+        // Operator commands from the admin api
+        #[cfg(feature = "api")]
+        let (admin_tx, admin_rx) = tokio::sync::mpsc::channel(ADMIN_CAPACITY);
+
+        // Build the network
         let keypair = Keypair::ed25519_from_bytes(private_key)?;
-        let (tx, rx) = Peer2Peer::build(keypair)?.start();
+        let mut config = NetworkConfig::from_env()?;
+        if config.namespace.is_empty() {
+            config.namespace = CHAIN_ID.to_string();
+        }
+        let registry = self.metrics.registry(&config.namespace, CHAIN_ID);
+        let network = Peer2Peer::build(keypair, config)?;
+        let control = network.handle();
+
+        // Bind the api before starting, a taken port fails the startup
+        #[cfg(feature = "api")]
+        let api = {
+            let config = game_api::ApiConfig::from_env()?;
+            let mut api = game_api::ApiServer::new(self.clone(), txb.clone(), admin_tx, config)
+                .with_metrics(network.metrics())
+                .with_metrics(Arc::new(registry));
+            if let Some(filter) = &self.log_filter {
+                api = api.with_log_filter(filter.clone());
+            }
+            api.bind()?
+        };
+        #[cfg(not(feature = "api"))]
+        drop(registry);
+
+        // Run network loop
+        let (tx, rx) = network.start();
+
+        // Restore persisted bans
+        match Reputation::load(BANS_PATH) {
+            Ok(reputation) => *self.reputation.write().unwrap() = reputation,
+            Err(e) => debug!("No ban list loaded: {e}"),
+        }
+
+        // Restore the chat history
+        match ChatHistory::load(CHAT_PATH) {
+            Ok(history) => *self.messages.write().unwrap() = history,
+            Err(e) => debug!("No chat history loaded: {e}"),
+        }
+
+        // Restore the mute and block lists, keeping the chat filter
+        match Moderation::load(MODERATION_PATH) {
+            Ok(loaded) => self.moderation.write().unwrap().restore(loaded),
+            Err(e) => debug!("No moderation list loaded: {e}"),
+        }
+
+        // Request a shutdown on termination signals
+        let exit_status = self.exit_status.clone();
+        tokio::spawn(async move {
+            if let Err(e) = shutdown::signal().await {
+                error!("Failed to listen for shutdown signals: {e}");
+            }
+            exit_status.exit();
+        });
+
+        // Probe the rpc and the peers for the health checks
+        tokio::spawn(self.clone().probe(client.provider.clone(), control.clone()));
 
         // Run core loop
-        #[cfg(feature = "interface")]
-        let (txb, rxb) = mpsc::channel();
-        tokio::spawn(self.clone().runner(
-            #[cfg(feature = "interface")]
+        let runner = tokio::spawn(self.clone().runner(
+            #[cfg(any(feature = "interface", feature = "api"))]
             rxb,
+            #[cfg(feature = "api")]
+            admin_rx,
             tx,
             rx,
+            control,
             client,
         ));
 
         // Run api loop
         #[cfg(feature = "api")]
-        tokio::spawn(game_api::ApiServer::new(self.clone()).run());
+        let api = tokio::spawn(api);
 
         // Run interface loop
         #[cfg(feature = "interface")]
-        game_interface::Interface::run(txb, self);
+        {
+            game_interface::Interface::run(txb, self.clone());
+            self.exit_status.exit();
+        }
 
         #[cfg(not(feature = "interface"))]
-        tokio::signal::ctrl_c().await?;
+        self.exit_status.wait().await;
 
+        // Wait for the subsystems to wind down
+        runner.await??;
+        #[cfg(feature = "api")]
+        api.await??;
+
+        info!("World stopped");
         Ok(())
     }
 
     /// Handles the message passing from input and network
     async fn runner(
         self,
-        #[cfg(feature = "interface")] rxb: mpsc::Receiver<GameEventMessage>,
+        #[cfg(any(feature = "interface", feature = "api"))] rxb: mpsc::Receiver<
+            Traced<GameEventMessage>,
+        >,
+        #[cfg(feature = "api")] mut admin: tokio::sync::mpsc::Receiver<AdminCommand>,
         tx: tokio::sync::mpsc::Sender<SignedMessage<GameEventMessage>>,
-        mut rx: tokio::sync::mpsc::Receiver<Message>,
-        mut client: RewarderClient,
+        mut rx: tokio::sync::mpsc::Receiver<ReceivedMessage>,
+        control: NetworkHandle,
+        client: RewarderClient,
     ) -> anyhow::Result<()> {
+        let peer_id = control.peer_id();
+        // The miner is only driven with the mine feature
+        #[cfg(feature = "mine")]
+        let (mut client, mut claims) = (client, tokio::task::JoinSet::new());
+
         while !self.exit_status.is_exit() {
-            // Listen for key events
-            #[cfg(feature = "interface")]
-            if let Ok(e) = rxb.try_recv() {
-                info!("Received Keyboard event: {e:?}");
-                self.update(&self.identifier, &e, &client).await;
-
-                // Send event to network
-                let message = SignedMessage::new(e, client.wallet.address());
-                if let Err(e) = tx.send_signed(message, &client.wallet).await {
-                    error!("Network error: {:?}", e);
-                };
+            let started = Instant::now();
+            self.health.tick();
+
+            // Listen for local events from the keyboard or the api
+            #[cfg(any(feature = "interface", feature = "api"))]
+            if let Ok(Traced { event: e, span }) = rxb.try_recv() {
+                // Follow the event from the span it was captured in
+                let span = info_span!(parent: &span, "local_event", event_type = e.name());
+                async {
+                    debug!(event_type = e.name(), "Received local event");
+                    self.update(&self.identifier, &e, &client).await;
+
+                    // Send event to network
+                    match self.seal(e) {
+                        Ok(e) => self.broadcast(e, &tx, &client, &peer_id).await,
+                        Err(e) => {
+                            warn!("Failed to send whisper: {e}");
+                            let (text, scope) = (ChatText::new(e.to_string()), ChatScope::Global);
+                            self.add_chat_message(self.identifier, "system".into(), text, scope);
+                        }
+                    }
+                }
+                .instrument(span)
+                .await;
+            }
+
+            // Apply the operator commands from the admin api
+            #[cfg(feature = "api")]
+            if let Ok(command) = admin.try_recv() {
+                debug!(command = command.name(), "Received admin command");
+                self.administer(
+                    command,
+                    &control,
+                    #[cfg(feature = "mine")]
+                    &mut claims,
+                    #[cfg(feature = "mine")]
+                    &client,
+                )
+                .await;
             }
 
             // Listen for network events
-            if let Ok(Some(m)) = rx.receive_signed()
-                && let Ok(signed) = SignedMessage::<GameEventMessage>::try_from(&m)
-            {
-                info!("Received Network message: {m:?} => {signed:?}");
-                self.update(&signed.address, &signed.data, &client).await;
+            // Only validated messages get relayed to the mesh
+            if let Ok(Some(m)) = rx.try_receive() {
+                let acceptance = self.receive(&m, &client).await;
+                let command = NetworkCommand::Validate(m.id, m.propagation_source, acceptance);
+                if let Err(e) = control.send(command).await {
+                    error!("Network control error: {e}");
+                }
             }
 
-            // Mine a new address
+            // Apply reputation changes to the network
+            let commands = self.reputation.write().unwrap().drain_commands();
+            for command in commands {
+                if let Err(e) = control.send(command).await {
+                    error!("Network control error: {e}");
+                }
+            }
+            if let Err(e) = self.reputation.write().unwrap().save(BANS_PATH) {
+                error!("Failed to save ban list: {e}");
+            }
+            if let Err(e) = self.moderation.write().unwrap().save(MODERATION_PATH) {
+                error!("Failed to save moderation list: {e}");
+            }
+            if let Err(e) = self.messages.write().unwrap().save(CHAT_PATH) {
+                error!("Failed to save chat history: {e}");
+            }
+
+            // Lease a nonce range within the player's zone
             #[cfg(feature = "mine")]
-            if let Ok(mined) = client.miner.run() {
-                info!("Mined address: {mined:?}");
-                let event = GameEvent::PlayerFound(mined);
-                self.update(&self.identifier, &event, &client).await;
-
-                // Send event to network
-                let message = SignedMessage::new(event, client.wallet.address());
-                if let Err(e) = tx.send_signed(message, &client.wallet).await {
-                    error!("Network error: {:?}", e);
-                };
+            let miner = *self.miner.read().unwrap();
+            #[cfg(feature = "mine")]
+            let range = match self.player_zone(&self.identifier) {
+                Some(zone) if miner.enabled => match self.lease_work(&zone, &client.miner) {
+                    Ok((range, events)) => {
+                        // Send work events to network
+                        for event in events {
+                            self.broadcast(GameEvent::Work(event), &tx, &client, &peer_id)
+                                .await;
+                        }
+                        Some(range)
+                    }
+                    Err(e) => {
+                        debug!("Failed to lease work in {}: {e}", zone.name);
+                        None
+                    }
+                },
+                _ => None,
+            };
+
+            // Mine new addresses within the leased range
+            #[cfg(feature = "mine")]
+            self.health.set_mining(range.is_some());
+            #[cfg(feature = "mine")]
+            if let Some(range) = range {
+                client.miner.assign(range);
+                for _ in 0..miner.workers {
+                    self.metrics.record_hash();
+                    let Ok(share) = client.miner.run() else {
+                        continue;
+                    };
+
+                    // Follow the share from its discovery to its claim
+                    let span = info_span!("share", nonce = %share.nonce, block = share.block);
+                    async {
+                        debug!(nonce = %share.nonce, block = share.block, "Found share");
+                        self.metrics.record_share_found();
+                        let event = GameEvent::PlayerFound(share);
+                        self.update(&self.identifier, &event, &client).await;
+
+                        // Send event to network
+                        self.broadcast(event, &tx, &client, &peer_id).await;
+                    }
+                    .instrument(span)
+                    .await;
+                }
             }
 
-            // If mined enough
-            // Spawn Claim Transaction
+            // Give up the lease once its range is mined
+            #[cfg(feature = "mine")]
+            let completed = if client.miner.is_exhausted() {
+                self.work.write().unwrap().complete(self.identifier)
+            } else {
+                None
+            };
             #[cfg(feature = "mine")]
-            if self.get_mined_count() >= 10
-                && let Ok(batch) = self.drain_mined_batch().try_into()
+            if let Some(event) = completed {
+                self.broadcast(GameEvent::Work(event), &tx, &client, &peer_id)
+                    .await;
+            }
+
+            // Spawn a claim transaction once enough is mined
+            #[cfg(feature = "mine")]
+            self.claim(&mut claims, &client);
+
+            // Reap finished claims
+            #[cfg(feature = "mine")]
+            while claims.try_join_next().is_some() {}
+            #[cfg(feature = "mine")]
+            self.health.set_claims(self.get_mined_count(), claims.len());
+
+            self.metrics.set_pending_shares(self.get_mined_count());
+            self.metrics.record_loop(started.elapsed());
+        }
+
+        info!("Shutting down");
+
+        // Let peers know the player left
+        if self.players.contains_player(&self.identifier) {
+            self.broadcast(GameEvent::Quit, &tx, &client, &peer_id)
+                .await;
+        }
+
+        // Wait for in-flight claims
+        #[cfg(feature = "mine")]
+        if !claims.is_empty() {
+            info!("Waiting for {} claim transactions", claims.len());
+            if tokio::time::timeout(shutdown::CLAIM_TIMEOUT, claims.join_all())
+                .await
+                .is_err()
             {
-                let contract = client.contract.clone();
-                tokio::spawn(async move {
-                    // Register the transaction
-                    let pending_tx = match contract.processMiningArray(batch).send().await {
-                        Ok(pending) => pending.register().await,
-                        Err(e) => return error!("Claim transaction error: {e}"),
-                    };
+                warn!("Claim transactions timed out");
+            }
+        }
 
-                    // Wait for the transaction to be mined
-                    let tx = match pending_tx {
-                        Ok(tx) => tx.await,
-                        Err(e) => return error!("Pending Transaction error: {e}"),
-                    };
+        // Persist state
+        if let Err(e) = self.reputation.write().unwrap().save(BANS_PATH) {
+            error!("Failed to save ban list: {e}");
+        }
+        if let Err(e) = self.moderation.write().unwrap().save(MODERATION_PATH) {
+            error!("Failed to save moderation list: {e}");
+        }
+        if let Err(e) = self.messages.write().unwrap().save(CHAT_PATH) {
+            error!("Failed to save chat history: {e}");
+        }
 
-                    match tx {
-                        Ok(tx) => info!("Claimed successfully {tx:?}"),
-                        Err(e) => error!("Claimed failed error: {e}"),
-                    };
+        // Flush pending gossip and close the connections
+        control.shutdown().await?;
+
+        Ok(())
+    }
+
+    /// Submits a claim transaction for a batch of mined shares
+    ///
+    /// The contract only takes full batches of `CLAIM_BATCH` shares
+    /// Returns the number of shares claimed, none while fewer are pending
+    #[cfg(feature = "mine")]
+    fn claim(
+        &self,
+        claims: &mut tokio::task::JoinSet<()>,
+        client: &RewarderClient,
+    ) -> Option<usize> {
+        let batch = self.drain_mined_batch()?;
+        let (contract, feed) = (client.contract.clone(), self.feed.clone());
+        let metrics = self.metrics.clone();
+
+        // Link the claim to the spans that mined its blocks
+        let span = info_span!("claim", count = CLAIM_BATCH, tx_hash = field::Empty);
+        for trace in self.block_traces.write().unwrap().drain(..) {
+            trace.link(&span);
+        }
+
+        claims.spawn(
+            async move {
+                let claim = |status, transaction| {
+                    metrics.record_claim(status);
+                    WorldEvent::Claim {
+                        status,
+                        count: CLAIM_BATCH,
+                        transaction,
+                    }
+                };
+
+                // Register the transaction
+                let (hash, pending_tx) = match contract.processMiningArray(batch).send().await {
+                    Ok(pending) => (pending.tx_hash().to_string(), pending.register().await),
+                    Err(e) => {
+                        feed.publish(claim(ClaimStatus::Failed, None));
+                        return error!("Claim transaction error: {e}");
+                    }
+                };
+                Span::current().record("tx_hash", hash.as_str());
+                info!(tx_hash = %hash, "Claim submitted");
+                feed.publish(claim(ClaimStatus::Submitted, Some(hash.clone())));
+
+                // Wait for the transaction to be mined
+                let tx = match pending_tx {
+                    Ok(tx) => tx.await,
+                    Err(e) => {
+                        error!(tx_hash = %hash, "Pending transaction error: {e}");
+                        feed.publish(claim(ClaimStatus::Failed, Some(hash)));
+                        return;
+                    }
+                };
+
+                match tx {
+                    Ok(_) => {
+                        info!(tx_hash = %hash, "Claimed successfully");
+                        feed.publish(claim(ClaimStatus::Confirmed, Some(hash)));
+                    }
+                    Err(e) => {
+                        error!(tx_hash = %hash, "Claim failed: {e}");
+                        feed.publish(claim(ClaimStatus::Failed, Some(hash)));
+                    }
+                };
+            }
+            .instrument(span),
+        );
+        Some(CLAIM_BATCH)
+    }
+
+    /// Applies an operator command from the admin api
+    #[cfg(feature = "api")]
+    async fn administer(
+        &self,
+        command: AdminCommand,
+        control: &NetworkHandle,
+        #[cfg(feature = "mine")] claims: &mut tokio::task::JoinSet<()>,
+        #[cfg(feature = "mine")] client: &RewarderClient,
+    ) {
+        match command {
+            AdminCommand::Miner(reply) => {
+                let _ = reply.send(Ok(self.miner_status()));
+            }
+            AdminCommand::Mining(enabled, reply) => {
+                let _ = reply.send(self.configure_miner(|miner| miner.enabled = enabled));
+            }
+            AdminCommand::Workers(workers, reply) => {
+                let status = match workers {
+                    1..=MAX_WORKERS => self.configure_miner(|miner| miner.workers = workers),
+                    _ => Err(format!("Workers must be between 1 and {MAX_WORKERS}")),
+                };
+                let _ = reply.send(status);
+            }
+            AdminCommand::Claim(reply) => {
+                #[cfg(feature = "mine")]
+                let claim = self.claim(claims, client).ok_or_else(|| {
+                    let pending = self.get_mined_count();
+                    format!("{pending} of the {CLAIM_BATCH} shares of a claim are mined")
+                });
+                #[cfg(not(feature = "mine"))]
+                let claim = Err("Mining is not enabled".to_string());
+                let _ = reply.send(claim);
+            }
+            AdminCommand::Peers(reply) => {
+                let peers = control.peers().await.map(|peers| {
+                    let reputation = self.reputation.read().unwrap();
+                    peers
+                        .into_iter()
+                        .map(|peer| PeerInfo {
+                            peer_id: peer.to_string(),
+                            score: reputation.peers.get(&peer).value,
+                        })
+                        .collect()
                 });
+                let _ = reply.send(peers.map_err(|e| e.to_string()));
+            }
+            AdminCommand::Ban(peer, duration, reply) => {
+                let banned = peer
+                    .parse::<PeerId>()
+                    .map(|peer| {
+                        let duration = duration.unwrap_or(Duration::from_secs(BAN_DURATION));
+                        self.reputation.write().unwrap().ban_peer(peer, duration);
+                    })
+                    .map_err(|e| format!("Invalid peer id {peer}: {e}"));
+                let _ = reply.send(banned);
+            }
+            AdminCommand::Dial(address, reply) => {
+                let dialed = match address.parse::<Multiaddr>() {
+                    Ok(address) => control.dial(address).await.map_err(|e| e.to_string()),
+                    Err(e) => Err(format!("Invalid multiaddr {address}: {e}")),
+                };
+                let _ = reply.send(dialed);
+            }
+        }
+    }
+
+    /// Gets the runtime settings of the local miner
+    #[cfg(feature = "api")]
+    fn miner_status(&self) -> MinerStatus {
+        MinerStatus {
+            pending: self.get_mined_count(),
+            ..*self.miner.read().unwrap()
+        }
+    }
+
+    /// Changes the settings of the local miner
+    #[cfg(feature = "api")]
+    fn configure_miner(
+        &self,
+        configure: impl FnOnce(&mut MinerStatus),
+    ) -> Result<MinerStatus, String> {
+        if !cfg!(feature = "mine") {
+            return Err("Mining is not enabled".to_string());
+        }
+
+        configure(&mut self.miner.write().unwrap());
+        let status = self.miner_status();
+        info!("Miner settings changed: {status:?}");
+        Ok(status)
+    }
+
+    /// Probes the rpc and the peers until the world exits
+    async fn probe(self, provider: DynProvider, control: NetworkHandle) {
+        let mut interval = tokio::time::interval(PROBE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = self.exit_status.wait() => break,
+                _ = interval.tick() => {}
             }
+
+            let block = tokio::time::timeout(PROBE_INTERVAL, provider.get_block_number()).await;
+            self.health.record_rpc(match block {
+                Ok(Ok(block)) => Ok(block),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err("RPC request timed out".to_string()),
+            });
+
+            match control.peers().await {
+                Ok(peers) => self.health.set_peers(peers.len()),
+                Err(e) => debug!("Failed to count peers: {e}"),
+            }
+        }
+    }
+
+    /// Handles a message received from the network
+    ///
+    /// Returns whether the message should be relayed to other peers
+    async fn receive(
+        &self,
+        received: &ReceivedMessage,
+        client: &RewarderClient,
+    ) -> MessageAcceptance {
+        let message = &received.message;
+        let peer = message.source;
+        self.metrics.record_received(received.kind);
+        self.health.record_gossip();
+        if self
+            .reputation
+            .read()
+            .unwrap()
+            .is_banned(None, peer.as_ref())
+        {
+            return MessageAcceptance::Ignore;
+        }
+
+        // Decode and verify the event
+        let signed = match SignedMessage::<GameEventMessage>::try_from(message) {
+            Ok(signed) => signed,
+            Err(e) => {
+                warn!(peer_id = ?peer, "Malformed message: {e}");
+                self.penalize(None, peer, Offense::Malformed);
+                return MessageAcceptance::Reject;
+            }
+        };
+
+        // Continue the trace of the sender
+        let span = signed.traced(info_span!(
+            "receive",
+            peer_id = ?peer,
+            address = %signed.address,
+            event_type = signed.data.name()
+        ));
+        self.accept(signed, peer, received.kind, client)
+            .instrument(span)
+            .await
+    }
+
+    /// Verifies, admits and applies a decoded message
+    async fn accept(
+        &self,
+        signed: SignedMessage<GameEventMessage>,
+        peer: Option<PeerId>,
+        kind: Option<TopicKind>,
+        client: &RewarderClient,
+    ) -> MessageAcceptance {
+        if let Err(e) = signed.verify() {
+            warn!(peer_id = ?peer, "Invalid signature: {e}");
+            self.penalize(None, peer, Offense::InvalidSignature);
+            return MessageAcceptance::Reject;
+        }
+
+        // Events must be published on the topic of their category
+        if kind != Some(signed.topic_kind()) {
+            warn!(peer_id = ?peer, address = %signed.address, "Message published on the wrong topic");
+            self.penalize(Some(signed.address), peer, Offense::Malformed);
+            return MessageAcceptance::Reject;
         }
 
+        // Messages are bound to the peer of their author, which gossipsub authenticates
+        let Some(peer) = peer.filter(|peer| signed.is_published_by(peer)) else {
+            warn!(peer_id = ?peer, address = %signed.address, "Message republished by another peer");
+            self.penalize(None, peer, Offense::Replay);
+            return MessageAcceptance::Reject;
+        };
+
+        if let Err(acceptance) = self
+            .admit(signed.address, peer, signed.sequence)
+            .and_then(|_| self.validate(&signed.address, &signed.data, client))
+        {
+            return acceptance;
+        }
+
+        // Remember the signer's key to seal whispers for them
+        if let Ok(key) = signed.recover_key() {
+            self.keys.write().unwrap().insert(signed.address, key);
+        }
+
+        debug!(
+            peer_id = ?peer,
+            address = %signed.address,
+            event_type = signed.data.name(),
+            "Received network message"
+        );
+        self.update(&signed.address, &signed.data, client).await;
+        MessageAcceptance::Accept
+    }
+
+    /// Checks the signer of a valid message against bans, replays and rate limits
+    fn admit(
+        &self,
+        address: Address,
+        peer: PeerId,
+        sequence: u64,
+    ) -> Result<(), MessageAcceptance> {
+        let mut reputation = self.reputation.write().unwrap();
+        if reputation.is_banned(Some(&address), None) {
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        // A sequence number already seen is a copy of an earlier message
+        if !reputation.observe(address, peer, sequence) {
+            debug!(%address, peer_id = %peer, sequence, "Replayed message");
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        if !reputation.addresses.record_message(address, unix_time()) {
+            debug!(%address, "Rate limited");
+            reputation.penalize(Some(address), Some(peer), Offense::Spam);
+            self.metrics.record_rejected(Offense::Spam);
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        reputation.reward(address, Some(peer));
         Ok(())
     }
 
+    /// Validates the content of an event before applying it
+    fn validate(
+        &self,
+        identifier: &Address,
+        event: &GameEventMessage,
+        client: &RewarderClient,
+    ) -> Result<(), MessageAcceptance> {
+        match event {
+            GameEvent::PlayerFound(share) => {
+                // Shares are only accounted once
+                if self.shares.read().unwrap().contains(share) {
+                    debug!(address = %identifier, nonce = %share.nonce, "Duplicate share");
+                    return Err(MessageAcceptance::Ignore);
+                }
+
+                let zone = self.map.zone_of_nonce(share.nonce);
+                if client.miner.verify(share).is_err() || zone.is_none() {
+                    warn!(address = %identifier, nonce = %share.nonce, "Invalid share");
+                    self.penalize(Some(*identifier), None, Offense::InvalidShare);
+                    return Err(MessageAcceptance::Reject);
+                }
+
+                // Players only announce their own shares
+                if share.address != *identifier {
+                    warn!(address = %identifier, miner = %share.address, "Share of another miner");
+                    self.penalize(Some(*identifier), None, Offense::InvalidShare);
+                    return Err(MessageAcceptance::Reject);
+                }
+
+                // Within a chunk leased by the player, positions may lag behind the leases
+                let leased = self.work.read().unwrap().table.is_held_by(
+                    &share.address,
+                    share.nonce,
+                    unix_time(),
+                );
+                if !leased {
+                    warn!(address = %identifier, nonce = %share.nonce, "Share mined outside the player lease");
+                    self.penalize(Some(*identifier), None, Offense::InvalidShare);
+                    return Err(MessageAcceptance::Reject);
+                }
+                self.metrics.record_share_verified();
+            }
+            // Players move one tile at a time
+            GameEvent::PlayerMovement(delta) if !delta.is_step() => {
+                warn!(address = %identifier, x = delta.x, y = delta.y, "Invalid movement");
+                self.penalize(Some(*identifier), None, Offense::Malformed);
+                return Err(MessageAcceptance::Reject);
+            }
+            // Jobs cover a whole zone with the standard chunks
+            GameEvent::Work(WorkEvent::Job(job)) => {
+                let expected = self
+                    .map
+                    .zone_of_nonce(job.range.start)
+                    .and_then(|zone| Self::zone_job(&zone, &client.miner).ok());
+                if expected.as_ref() != Some(job) {
+                    warn!(address = %identifier, id = %job.id, "Invalid job");
+                    self.penalize(Some(*identifier), None, Offense::Malformed);
+                    return Err(MessageAcceptance::Reject);
+                }
+            }
+            // Whispers are sealed before reaching the network
+            GameEvent::Whisper(..) => {
+                warn!(address = %identifier, "Unsealed whisper");
+                self.penalize(Some(*identifier), None, Offense::Malformed);
+                return Err(MessageAcceptance::Reject);
+            }
+            GameEvent::DirectMessage(sealed) => {
+                if Address::try_from(sealed.recipient.as_slice()).is_err() {
+                    warn!(address = %identifier, "Invalid direct message recipient");
+                    self.penalize(Some(*identifier), None, Offense::Malformed);
+                    return Err(MessageAcceptance::Reject);
+                }
+
+                let length = sealed.ciphertext.len().saturating_sub(chat::TAG_LEN);
+                self.limit_chat(identifier, length)?;
+            }
+            GameEvent::ChatMessage(text) => {
+                self.check_chat(identifier, text)?;
+                self.limit_chat(identifier, text.text.len())?;
+            }
+            GameEvent::ChannelMessage(channel, text) => {
+                if channel.is_empty() || channel.len() > MAX_CHANNEL_LENGTH {
+                    warn!(address = %identifier, "Invalid channel name");
+                    self.penalize(Some(*identifier), None, Offense::Malformed);
+                    return Err(MessageAcceptance::Reject);
+                }
+
+                self.check_chat(identifier, text)?;
+                self.limit_chat(identifier, text.text.len())?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks a chat message timestamp and drops the ones already seen
+    fn check_chat(&self, identifier: &Address, text: &ChatText) -> Result<(), MessageAcceptance> {
+        let now = unix_millis();
+        if text.timestamp > now.saturating_add(MAX_CLOCK_SKEW) {
+            warn!(address = %identifier, "Chat message from the future");
+            self.penalize(Some(*identifier), None, Offense::Malformed);
+            return Err(MessageAcceptance::Reject);
+        }
+        if text.timestamp.saturating_add(MAX_MESSAGE_AGE) < now {
+            debug!(address = %identifier, "Stale chat message");
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        let id = chat::message_id(identifier, text).map_err(|_| MessageAcceptance::Ignore)?;
+        if self.messages.read().unwrap().contains(&id) {
+            debug!(address = %identifier, %id, "Duplicate chat message");
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a chat message against the length and rate limits
+    fn limit_chat(&self, identifier: &Address, length: usize) -> Result<(), MessageAcceptance> {
+        if length > MAX_MESSAGE_LENGTH {
+            warn!(address = %identifier, "Oversized chat message");
+            self.penalize(Some(*identifier), None, Offense::Malformed);
+            return Err(MessageAcceptance::Reject);
+        }
+
+        let allowed = self
+            .moderation
+            .write()
+            .unwrap()
+            .allow(*identifier, Instant::now());
+        if !allowed {
+            debug!(address = %identifier, "Chat rate limited");
+            self.penalize(Some(*identifier), None, Offense::Spam);
+            return Err(MessageAcceptance::Ignore);
+        }
+
+        Ok(())
+    }
+
+    /// Penalizes a misbehaving sender
+    fn penalize(&self, address: Option<Address>, peer: Option<PeerId>, offense: Offense) {
+        self.metrics.record_rejected(offense);
+        self.reputation
+            .write()
+            .unwrap()
+            .penalize(address, peer, offense);
+    }
+
+    /// Seals a whisper into a direct message
+    ///
+    /// Other events are left untouched
+    #[cfg(any(feature = "interface", feature = "api"))]
+    fn seal(&self, event: GameEventMessage) -> Result<GameEventMessage> {
+        let GameEvent::Whisper(name, message) = event else {
+            return Ok(event);
+        };
+
+        let recipient = self
+            .resolve(&name)
+            .ok_or_else(|| anyhow!("Unknown player {name}"))?;
+        let key = self
+            .keys
+            .read()
+            .unwrap()
+            .get(&recipient)
+            .cloned()
+            .ok_or_else(|| anyhow!("No key known for {name} yet"))?;
+
+        let text = ChatText::new(message);
+        Ok(GameEvent::DirectMessage(chat::seal(
+            recipient, &key, &text,
+        )?))
+    }
+
+    /// Gets the display name of a player
+    ///
+    /// Uses the ENS name when one is set
+    async fn name_of(&self, identifier: &Address, client: &RewarderClient) -> String {
+        // Check Cache
+        let cached_name = self.ens_cache.read().unwrap().get(identifier).cloned();
+        self.metrics.record_ens_lookup(cached_name.is_some());
+        match cached_name {
+            Some(n) => n,
+            // Get ENS name
+            None => match client.ens.nameForAddr(*identifier).call().await {
+                Ok(n) if n.is_empty() => identifier.to_string(),
+                Ok(n) => {
+                    self.ens_cache
+                        .write()
+                        .unwrap()
+                        .insert(*identifier, n.clone());
+                    n
+                }
+                Err(e) => {
+                    error!(address = %identifier, "Failed to get ENS name: {e}");
+                    identifier.to_string()
+                }
+            },
+        }
+    }
+
+    /// Signs and sends an event to the network
+    async fn broadcast(
+        &self,
+        event: GameEventMessage,
+        tx: &tokio::sync::mpsc::Sender<SignedMessage<GameEventMessage>>,
+        client: &RewarderClient,
+        peer_id: &PeerId,
+    ) {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        let message =
+            SignedMessage::new(event, client.wallet.address()).published_by(peer_id, sequence);
+        let topic = message.topic_kind();
+        match tx.send_signed(message, &client.wallet).await {
+            Ok(_) => self.metrics.record_published(topic),
+            Err(e) => error!("Network error: {e}"),
+        };
+    }
+
     /// Updates the world
     ///
     /// Based on the Events received
+    #[instrument(skip_all, fields(address = %identifier, event_type = event.name()))]
     pub async fn update(
         &self,
         identifier: &Address,
@@ -215,60 +1071,149 @@ where
     ) {
         match event {
             GameEvent::PlayerMovement(p) => {
+                // New players start from their spawn point
+                let current = self
+                    .players
+                    .update_player(identifier, |player| player.position)
+                    .unwrap_or_else(|| self.map.spawn_point(identifier.as_slice()));
+
+                // Validate against the map collisions
+                let Some(target) = current.checked_add(*p) else {
+                    warn!(address = %identifier, "Movement out of bounds");
+                    return;
+                };
+                if !self.map.is_walkable(&target) {
+                    trace!(address = %identifier, x = target.x, y = target.y, "Player blocked");
+                    return;
+                }
+
                 // Update player position
-                info!("Player {identifier:?} moved by: {p:?}");
+                trace!(address = %identifier, x = target.x, y = target.y, "Player moved");
                 let res = self.players.update_player(identifier, |player| {
-                    player.position += *p;
+                    player.position = target;
                 });
 
                 // If new player, add to players pool
+                let (player, x, y) = (identifier.to_string(), target.x.into(), target.y.into());
                 if res.is_none() {
                     let mut new_player = Character::new(*identifier, Default::default(), (0, 0));
-                    new_player.position = *p;
+                    new_player.position = target;
                     self.players.add_player(*identifier, new_player);
+                    self.feed.publish(WorldEvent::PlayerJoined { player, x, y });
+                } else {
+                    self.feed.publish(WorldEvent::PlayerMoved { player, x, y });
                 }
             }
-            GameEvent::PlayerFound(f) => {
-                // Verify the mined address
-                if let Err(e) = client.miner.verify(f.0, f.1) {
-                    error!("Failed to verify mined {f:?}: {e}");
+            GameEvent::PlayerFound(share) => {
+                // Verify the share against its claimed target
+                if let Err(e) = client.miner.verify(share) {
+                    error!(address = %identifier, nonce = %share.nonce, "Failed to verify mined share: {e}");
                     return;
                 }
 
-                let mined_block = MinedBlock::new(f.0, f.1).unwrap();
-                info!("Player {identifier:?} mined: {mined_block:?}");
-
-                // Add to mined blocks
-                self.mined.write().unwrap().insert(mined_block);
-            }
-            GameEvent::ChatMessage(message) => {
-                // Check Cache
-                let cached_name = self.ens_cache.read().unwrap().get(identifier).cloned();
-                let identifier = match cached_name {
-                    Some(n) => n,
-                    // Get ENS name
-                    None => match client.ens.nameForAddr(*identifier).call().await {
-                        Ok(n) if n.is_empty() => identifier.to_string(),
-                        Ok(n) => {
-                            self.ens_cache
-                                .write()
-                                .unwrap()
-                                .insert(*identifier, n.clone());
-                            n
-                        }
-                        Err(e) => {
-                            error!("Failed to get ENS name for address {identifier:?}: {e}");
-                            identifier.to_string()
-                        }
-                    },
+                // Nonce must belong to a mining zone
+                let Some(zone) = self.map.zone_of_nonce(share.nonce) else {
+                    error!(address = %identifier, nonce = %share.nonce, "Mined nonce is outside of any zone");
+                    return;
                 };
 
-                info!("Player {identifier:?} sent chat message: {message}");
-                self.add_chat_message(identifier, message.clone());
+                // Account the work of the share once
+                if !self.shares.write().unwrap().insert(*share, unix_millis()) {
+                    return;
+                }
+                let work = share.work().saturating_to::<u64>();
+                *self
+                    .contributions
+                    .write()
+                    .unwrap()
+                    .entry(share.address)
+                    .or_default() += work;
+                *self
+                    .zone_shares
+                    .write()
+                    .unwrap()
+                    .entry(zone.index)
+                    .or_default() += work;
+                debug!(
+                    address = %identifier,
+                    nonce = %share.nonce,
+                    zone = %zone.name,
+                    "Player found share"
+                );
+                self.feed.publish(WorldEvent::Share {
+                    player: identifier.to_string(),
+                    miner: share.address.to_string(),
+                    nonce: share.nonce.to_string(),
+                    zone: zone.name.clone(),
+                    block: share.block,
+                });
+
+                // Full solutions are kept for claiming
+                if share.block {
+                    let mined_block = MinedBlock::new(share.address, share.nonce).unwrap();
+                    info!(
+                        address = %identifier,
+                        miner = %share.address,
+                        nonce = %share.nonce,
+                        zone = %zone.name,
+                        "Player mined a block"
+                    );
+                    self.mined.write().unwrap().insert(mined_block);
+                    #[cfg(feature = "mine")]
+                    if let Some(trace) = TraceContext::current() {
+                        self.block_traces.write().unwrap().push(trace);
+                    }
+                }
+            }
+            GameEvent::ChatMessage(text) => {
+                let name = self.name_of(identifier, client).await;
+                debug!(address = %identifier, %name, "Player sent chat message");
+                self.add_chat_message(*identifier, name, text.clone(), ChatScope::Global);
+            }
+            GameEvent::ChannelMessage(channel, text) => {
+                let name = self.name_of(identifier, client).await;
+                debug!(address = %identifier, %name, %channel, "Player sent chat message");
+                let scope = ChatScope::Channel(channel.clone());
+                self.add_chat_message(*identifier, name, text.clone(), scope);
+            }
+            GameEvent::Whisper(name, message) => {
+                // Keep a copy of the local player's own whisper
+                let sender = self.name_of(identifier, client).await;
+                let (text, scope) = (
+                    ChatText::new(message.clone()),
+                    ChatScope::Direct(name.clone()),
+                );
+                self.add_chat_message(*identifier, sender, text, scope);
+            }
+            GameEvent::DirectMessage(sealed) => {
+                if sealed.recipient != self.identifier.as_slice() {
+                    return;
+                }
+
+                match chat::open(client.wallet.credential(), sealed) {
+                    // The time of a sealed text is only known once opened
+                    Ok(text) if self.check_chat(identifier, &text).is_err() => {}
+                    Ok(text) => {
+                        let name = self.name_of(identifier, client).await;
+                        debug!(address = %identifier, %name, "Player whispered");
+                        let scope = ChatScope::Direct(name.clone());
+                        self.add_chat_message(*identifier, name, text, scope);
+                    }
+                    Err(e) => warn!(address = %identifier, "Failed to open direct message: {e}"),
+                }
+            }
+            GameEvent::Work(work) => {
+                let mut pool = self.work.write().unwrap();
+                if let Err(e) = pool.apply(*identifier, work, unix_time()) {
+                    warn!(address = %identifier, "Rejected work event: {e}");
+                }
             }
             GameEvent::Quit => {
-                info!("Player {identifier:?} quit");
+                info!(address = %identifier, "Player quit");
                 self.players.remove_player(identifier);
+                let player = identifier.to_string();
+                self.feed.publish(WorldEvent::PlayerQuit { player });
+                self.work.write().unwrap().table.release_all(identifier);
 
                 // Quit if the local player quit
                 if identifier == &self.identifier {
@@ -278,17 +1223,20 @@ where
         }
     }
 
-    /// Gets the current mined addresses
-    pub fn drain_mined_batch(&self) -> Vec<Rewarder::MinerData> {
-        self.mined
-            .write()
-            .unwrap()
-            .drain()
-            .map(|mined_block| Rewarder::MinerData {
+    /// Takes a claim batch out of the mined addresses
+    ///
+    /// Returns none while fewer than a batch are mined
+    pub fn drain_mined_batch(&self) -> Option<[Rewarder::MinerData; CLAIM_BATCH]> {
+        let mut mined = self.mined.write().unwrap();
+        let batch: Vec<MinedBlock> = mined.iter().take(CLAIM_BATCH).copied().collect();
+        let batch: [MinedBlock; CLAIM_BATCH] = batch.try_into().ok()?;
+        Some(batch.map(|mined_block| {
+            mined.remove(&mined_block);
+            Rewarder::MinerData {
                 minerAddress: mined_block.address,
                 nonce: mined_block.nonce,
-            })
-            .collect()
+            }
+        }))
     }
 
     /// Get the mined addresses count
@@ -296,10 +1244,95 @@ where
         self.mined.read().unwrap().len()
     }
 
+    /// Gets the zone a player is standing in
+    ///
+    /// Players not seen moving yet stand on their spawn point
+    pub fn player_zone(&self, identifier: &Address) -> Option<Zone> {
+        let position = self
+            .players
+            .update_player(identifier, |player| player.position)
+            .unwrap_or_else(|| self.map.spawn_point(identifier.as_slice()));
+        self.map.zone_at(&position)
+    }
+
+    /// Leases a chunk of the zone job for the local player
+    ///
+    /// Returns the nonce range to mine and the work events to broadcast
+    pub fn lease_work(
+        &self,
+        zone: &Zone,
+        miner: &Miner,
+    ) -> Result<(std::ops::Range<U256>, Vec<WorkEvent>)> {
+        let mut pool = self.work.write().unwrap();
+
+        // Reuse the job while staying in the same zone
+        let range = zone.nonce_range();
+        let job = match pool.current_job().filter(|job| job.range == range) {
+            Some(job) => job.clone(),
+            None => Self::zone_job(zone, miner)?,
+        };
+
+        pool.lease(self.identifier, job, unix_time())
+    }
+
+    /// Builds the job mining the whole nonce range of a zone
+    fn zone_job(zone: &Zone, miner: &Miner) -> Result<Job> {
+        Job::new(
+            Miner::BACKEND,
+            miner.params(),
+            zone.nonce_range(),
+            U256::ONE << CHUNK_BITS,
+        )
+    }
+
+    /// Gets the accumulated share work of a miner
+    pub fn get_contribution(&self, miner: &Address) -> u64 {
+        self.contributions
+            .read()
+            .unwrap()
+            .get(miner)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Adds a chat message to the messages pool
-    pub fn add_chat_message(&self, identifier: String, message: String) {
-        let mut messages = self.messages.write().unwrap();
-        messages.push(ChatMessage::new(identifier, message));
+    ///
+    /// Applies the block list and chat filter, keeping a bounded history
+    pub fn add_chat_message(
+        &self,
+        sender: Address,
+        name: String,
+        text: ChatText,
+        scope: ChatScope,
+    ) {
+        let id = match chat::message_id(&sender, &text) {
+            Ok(id) => id,
+            Err(e) => return error!("Failed to identify chat message: {e}"),
+        };
+        let Some(message) = self
+            .moderation
+            .read()
+            .unwrap()
+            .moderate(&sender, &text.text)
+        else {
+            debug!(address = %sender, "Dropped chat message");
+            return;
+        };
+
+        let text = ChatText {
+            text: message,
+            ..text
+        };
+        let message = ChatMessage::new(id, sender.to_string(), name, text, scope);
+        if !self.messages.write().unwrap().push(sender, message.clone()) {
+            return debug!(address = %sender, "Duplicate chat message");
+        }
+
+        // Muted players and decrypted whispers stay out of the public feed
+        let direct = matches!(message.scope, ChatScope::Direct(_));
+        if !direct && !self.moderation.read().unwrap().is_muted(&sender) {
+            self.feed.publish(WorldEvent::Chat { message });
+        }
     }
 }
 
@@ -311,15 +1344,29 @@ impl<I: Clone, B, T> Identifier for World<I, B, T> {
     }
 }
 
-impl<I, B, T> WorldState for World<I, B, T>
+impl<B, T> World<Address, B, T> {
+    /// Resolves a player from its address or a known ENS name
+    fn resolve(&self, name: &str) -> Option<Address> {
+        name.parse().ok().or_else(|| {
+            self.ens_cache
+                .read()
+                .unwrap()
+                .iter()
+                .find(|(_, n)| n.as_str() == name)
+                .map(|(address, _)| *address)
+        })
+    }
+}
+
+impl<B, T> WorldState for World<Address, B, T>
 where
-    I: Clone,
     B: Clone,
     T: Copy + Clone + Into<f64>,
 {
-    type Player = Character<I, B, T>;
+    type Player = Character<Address, B, T>;
     type Message = ChatMessage;
     type MiningBatch = MinedBlock;
+    type Map = TileMap;
 
     fn exit_status(&self) -> Arc<ExitStatus> {
         self.exit_status.clone()
@@ -338,20 +1385,148 @@ where
     }
 
     fn get_chat_messages(&self) -> Vec<Self::Message> {
-        self.messages.read().unwrap().clone()
+        let moderation = self.moderation.read().unwrap();
+        self.messages
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(sender, _)| !moderation.is_muted(sender))
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+
+    fn mute(&self, name: &str, muted: bool) -> bool {
+        let Some(address) = self.resolve(name) else {
+            return false;
+        };
+
+        self.moderation.write().unwrap().mute(address, muted);
+        true
+    }
+
+    fn block(&self, name: &str, blocked: bool) -> bool {
+        let Some(address) = self.resolve(name) else {
+            return false;
+        };
+
+        self.moderation.write().unwrap().block(address, blocked);
+        true
+    }
+
+    fn get_map(&self) -> Arc<Self::Map> {
+        self.map.clone()
+    }
+
+    fn get_bans(&self) -> Vec<BanEntry> {
+        self.reputation.read().unwrap().bans()
+    }
+
+    fn feed(&self) -> Arc<EventFeed> {
+        self.feed.clone()
+    }
+
+    fn record_fps(&self, fps: f64) {
+        self.metrics.record_fps(fps);
+    }
+
+    fn health(&self) -> WorldHealth {
+        self.health.report()
+    }
+
+    fn get_miner_contribution(&self, miner: &str) -> u64 {
+        let Ok(miner) = miner.parse::<Address>() else {
+            return 0;
+        };
+        let contributions = self.contributions.read().unwrap();
+        contributions.get(&miner).copied().unwrap_or_default()
+    }
+
+    fn get_miner_shares(&self, miner: &str) -> Vec<MinedShare> {
+        let Ok(miner) = miner.parse::<Address>() else {
+            return Vec::new();
+        };
+
+        let shares = self.shares.read().unwrap().shares_of(&miner);
+        shares
+            .into_iter()
+            .map(|(nonce, block, timestamp)| MinedShare {
+                miner: miner.to_string(),
+                nonce: nonce.to_string(),
+                block,
+                timestamp,
+            })
+            .collect()
+    }
+
+    fn get_zones(&self) -> Vec<ZoneStatus> {
+        let shares = self.zone_shares.read().unwrap();
+        let total = shares.values().sum::<u64>().max(1) as f64;
+
+        // Zone of every player
+        let players = self.players.players.read().unwrap();
+        let positions = players
+            .iter()
+            .map(|(id, player)| {
+                let (x, y): (f64, f64) = (player.position.x.into(), player.position.y.into());
+                (id, Position::new(x as i32, y as i32))
+            })
+            .collect::<Vec<_>>();
+
+        self.map
+            .zones()
+            .iter()
+            .map(|zone| {
+                let inside = positions.iter().filter(|(_, p)| zone.contains(p));
+                let local = inside.clone().any(|(id, _)| **id == self.identifier);
+
+                ZoneStatus {
+                    players: inside.count(),
+                    share: shares.get(&zone.index).copied().unwrap_or_default() as f64 / total,
+                    local,
+                    name: zone.name.clone(),
+                }
+            })
+            .collect()
     }
 }
 
 /// Mined Block
 ///
 /// Responsible for storing the mined block information
-#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq, Hash)]
+/// Identified by its miner and nonce, whenever it was received
+#[derive(Debug, Serialize, Copy, Clone, JsonSchema)]
 pub struct MinedBlock {
+    #[schemars(with = "String")]
     pub address: Address,
+    #[schemars(with = "String")]
     pub nonce: U256,
     pub timestamp: u64,
 }
 
+impl PartialEq for MinedBlock {
+    fn eq(&self, other: &Self) -> bool {
+        (self.address, self.nonce) == (other.address, other.nonce)
+    }
+}
+
+impl Eq for MinedBlock {}
+
+impl Hash for MinedBlock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.address, self.nonce).hash(state);
+    }
+}
+
+impl Record for MinedBlock {
+    fn owner(&self) -> String {
+        self.address.to_string()
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp * 1000
+    }
+}
+
 impl MinedBlock {
     /// Creates a new mined block
     pub fn new(address: Address, nonce: U256) -> anyhow::Result<Self> {
@@ -0,0 +1,232 @@
+use crate::reputation::Offense;
+use game_network::TopicKind;
+use game_primitives::feed::ClaimStatus;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::Registry;
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::time::{Duration, Instant};
+
+/// Window over which the hashrate is measured
+const HASHRATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Labels of the gossip messages
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct GossipLabels {
+    direction: &'static str,
+    topic: &'static str,
+}
+
+/// Labels of the rejected messages
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ReasonLabels {
+    reason: &'static str,
+}
+
+/// Labels of the claim transactions
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct StatusLabels {
+    status: &'static str,
+}
+
+/// Labels of the ENS lookups
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct CacheLabels {
+    result: &'static str,
+}
+
+/// World Metrics
+///
+/// Counters and gauges of the world activity
+/// Exported along the network metrics by the API
+#[derive(Debug)]
+pub struct WorldMetrics {
+    gossip: Family<GossipLabels, Counter>,
+    rejected: Family<ReasonLabels, Counter>,
+    hashes: Counter,
+    hashrate: Gauge<f64, AtomicU64>,
+    window: Mutex<(Instant, u64)>,
+    shares_found: Counter,
+    shares_verified: Counter,
+    pending_shares: Gauge,
+    claims: Family<StatusLabels, Counter>,
+    ens_lookups: Family<CacheLabels, Counter>,
+    fps: Gauge<f64, AtomicU64>,
+    loop_latency: Histogram,
+}
+
+impl Default for WorldMetrics {
+    fn default() -> Self {
+        Self {
+            gossip: Default::default(),
+            rejected: Default::default(),
+            hashes: Default::default(),
+            hashrate: Default::default(),
+            window: Mutex::new((Instant::now(), 0)),
+            shares_found: Default::default(),
+            shares_verified: Default::default(),
+            pending_shares: Default::default(),
+            claims: Default::default(),
+            ens_lookups: Default::default(),
+            fps: Default::default(),
+            // From 100us to 26s
+            loop_latency: Histogram::new(exponential_buckets(0.0001, 4.0, 10)),
+        }
+    }
+}
+
+impl WorldMetrics {
+    /// Creates new world metrics
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Builds the registry of the metrics, labelled by network and chain
+    pub fn registry(&self, network: &str, chain_id: u64) -> Registry {
+        let labels = [
+            (Cow::Borrowed("network"), Cow::Owned(network.to_string())),
+            (Cow::Borrowed("chain"), Cow::Owned(chain_id.to_string())),
+        ];
+        let mut registry = Registry::with_prefix_and_labels("world", labels.into_iter());
+
+        registry.register(
+            "gossip_messages",
+            "Gossip messages received and published by topic",
+            self.gossip.clone(),
+        );
+        registry.register(
+            "rejected_messages",
+            "Gossip messages rejected by reason",
+            self.rejected.clone(),
+        );
+        registry.register("hashes", "Nonces hashed by the miner", self.hashes.clone());
+        registry.register(
+            "hashrate",
+            "Hashes per second of the miner",
+            self.hashrate.clone(),
+        );
+        registry.register(
+            "shares_found",
+            "Shares found by the local miner",
+            self.shares_found.clone(),
+        );
+        registry.register(
+            "shares_verified",
+            "Shares of peers verified",
+            self.shares_verified.clone(),
+        );
+        registry.register(
+            "pending_shares",
+            "Mined blocks waiting for a claim",
+            self.pending_shares.clone(),
+        );
+        registry.register(
+            "claims",
+            "Claim transactions by status",
+            self.claims.clone(),
+        );
+        registry.register(
+            "ens_lookups",
+            "ENS name lookups by cache result",
+            self.ens_lookups.clone(),
+        );
+        registry.register("interface_fps", "Interface frame rate", self.fps.clone());
+        registry.register(
+            "runner_loop_seconds",
+            "Duration of a runner loop iteration",
+            self.loop_latency.clone(),
+        );
+
+        registry
+    }
+
+    /// Records a gossip message received from the network
+    pub fn record_received(&self, topic: Option<TopicKind>) {
+        let topic = topic.map(|topic| topic.name()).unwrap_or("unknown");
+        let labels = GossipLabels {
+            direction: "in",
+            topic,
+        };
+        self.gossip.get_or_create(&labels).inc();
+    }
+
+    /// Records a gossip message published to the network
+    pub fn record_published(&self, topic: TopicKind) {
+        let labels = GossipLabels {
+            direction: "out",
+            topic: topic.name(),
+        };
+        self.gossip.get_or_create(&labels).inc();
+    }
+
+    /// Records a message rejected for an offense
+    pub fn record_rejected(&self, offense: Offense) {
+        let labels = ReasonLabels {
+            reason: offense.name(),
+        };
+        self.rejected.get_or_create(&labels).inc();
+    }
+
+    /// Records a hashed nonce
+    ///
+    /// Updates the hashrate once the window has elapsed
+    pub fn record_hash(&self) {
+        let total = self.hashes.inc() + 1;
+
+        let mut window = self.window.lock().unwrap();
+        let elapsed = window.0.elapsed();
+        if elapsed >= HASHRATE_WINDOW {
+            let hashes = total.saturating_sub(window.1);
+            self.hashrate.set(hashes as f64 / elapsed.as_secs_f64());
+            *window = (Instant::now(), total);
+        }
+    }
+
+    /// Records a share found by the local miner
+    pub fn record_share_found(&self) {
+        self.shares_found.inc();
+    }
+
+    /// Records a verified share of a peer
+    pub fn record_share_verified(&self) {
+        self.shares_verified.inc();
+    }
+
+    /// Sets the number of mined blocks waiting for a claim
+    pub fn set_pending_shares(&self, count: usize) {
+        self.pending_shares.set(count as i64);
+    }
+
+    /// Records a claim transaction status
+    pub fn record_claim(&self, status: ClaimStatus) {
+        let status = match status {
+            ClaimStatus::Submitted => "submitted",
+            ClaimStatus::Confirmed => "confirmed",
+            ClaimStatus::Failed => "failed",
+        };
+        self.claims.get_or_create(&StatusLabels { status }).inc();
+    }
+
+    /// Records an ENS lookup, served from the cache or not
+    pub fn record_ens_lookup(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.ens_lookups
+            .get_or_create(&CacheLabels { result })
+            .inc();
+    }
+
+    /// Sets the interface frame rate
+    pub fn record_fps(&self, fps: f64) {
+        self.fps.set(fps);
+    }
+
+    /// Records the duration of a runner loop iteration
+    pub fn record_loop(&self, elapsed: Duration) {
+        self.loop_latency.observe(elapsed.as_secs_f64());
+    }
+}
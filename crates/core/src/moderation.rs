@@ -0,0 +1,262 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Default path to the persisted mute and block lists
+pub const MODERATION_PATH: &str = "./moderation.json";
+/// Chat messages a sender can burst
+const CHAT_BURST: f64 = 5.0;
+/// Chat messages a sender regains per second
+const CHAT_REFILL_RATE: f64 = 0.5;
+/// Buckets kept before pruning the full ones
+const MAX_BUCKETS: usize = 1024;
+
+/// Chat Filter
+///
+/// Hook applied to every chat message before it is displayed
+/// Returns the message to display, `None` drops it
+#[derive(Clone)]
+pub struct ChatFilter(Arc<Filter>);
+
+/// Rewrites or drops a chat message
+type Filter = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl ChatFilter {
+    /// Creates a new chat filter
+    pub fn new(filter: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(filter))
+    }
+}
+
+impl Debug for ChatFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ChatFilter")
+    }
+}
+
+/// Token Bucket
+///
+/// Rate limit of a single sender
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket
+    fn new(now: Instant) -> Self {
+        let tokens = CHAT_BURST;
+        Self {
+            tokens,
+            updated: now,
+        }
+    }
+
+    /// Refills the bucket up to the given time
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * CHAT_REFILL_RATE).min(CHAT_BURST);
+        self.updated = now;
+    }
+
+    /// Takes a token from the bucket
+    ///
+    /// Returns false if the bucket is empty
+    fn take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Moderation
+///
+/// Local chat rate limits, mute and block lists
+/// Muted senders are hidden, blocked senders are dropped
+#[derive(Debug)]
+pub struct Moderation<K> {
+    muted: HashSet<K>,
+    blocked: HashSet<K>,
+    buckets: HashMap<K, TokenBucket>,
+    filter: Option<ChatFilter>,
+    changed: bool,
+}
+
+impl<K> Default for Moderation<K> {
+    fn default() -> Self {
+        Self {
+            muted: Default::default(),
+            blocked: Default::default(),
+            buckets: Default::default(),
+            filter: None,
+            changed: false,
+        }
+    }
+}
+
+impl<K> Moderation<K>
+where
+    K: Eq + Hash + Copy + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Creates a new moderation
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the chat filter hook
+    pub fn set_filter(&mut self, filter: ChatFilter) {
+        self.filter = Some(filter);
+    }
+
+    /// Counts a chat message against the sender's rate limit
+    ///
+    /// Returns false if the sender exceeded the limit
+    pub fn allow(&mut self, key: K, now: Instant) -> bool {
+        if self.buckets.len() >= MAX_BUCKETS {
+            // Full buckets are equivalent to new ones
+            self.buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                bucket.tokens < CHAT_BURST
+            });
+        }
+
+        self.buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(now))
+            .take(now)
+    }
+
+    /// Checks if the sender is muted
+    pub fn is_muted(&self, key: &K) -> bool {
+        self.muted.contains(key)
+    }
+
+    /// Checks if the sender is blocked
+    pub fn is_blocked(&self, key: &K) -> bool {
+        self.blocked.contains(key)
+    }
+
+    /// Mutes or unmutes a sender
+    pub fn mute(&mut self, key: K, muted: bool) {
+        self.changed |= match muted {
+            true => self.muted.insert(key),
+            false => self.muted.remove(&key),
+        };
+    }
+
+    /// Blocks or unblocks a sender
+    pub fn block(&mut self, key: K, blocked: bool) {
+        self.changed |= match blocked {
+            true => self.blocked.insert(key),
+            false => self.blocked.remove(&key),
+        };
+    }
+
+    /// Applies the block list and the filter to a chat message
+    ///
+    /// Returns the message to keep, if any
+    pub fn moderate(&self, key: &K, message: &str) -> Option<String> {
+        if self.is_blocked(key) {
+            return None;
+        }
+
+        match &self.filter {
+            Some(filter) => (filter.0)(message),
+            None => Some(message.to_string()),
+        }
+    }
+
+    /// Restores loaded mute and block lists
+    ///
+    /// Keeps the current filter
+    pub fn restore(&mut self, loaded: Self) {
+        self.muted = loaded.muted;
+        self.blocked = loaded.blocked;
+    }
+
+    /// Saves the mute and block lists if they changed
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        if !std::mem::take(&mut self.changed) {
+            return Ok(());
+        }
+
+        let list = ModerationList {
+            muted: self.muted.clone(),
+            blocked: self.blocked.clone(),
+        };
+
+        std::fs::write(path, serde_json::to_string_pretty(&list)?)?;
+        Ok(())
+    }
+
+    /// Loads the mute and block lists
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let list: ModerationList<K> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let moderation = Self {
+            muted: list.muted,
+            blocked: list.blocked,
+            ..Default::default()
+        };
+
+        Ok(moderation)
+    }
+}
+
+/// Moderation List
+///
+/// Persisted form of the mute and block lists
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "K: Eq + Hash + Serialize + for<'a> Deserialize<'a>")]
+struct ModerationList<K> {
+    muted: HashSet<K>,
+    blocked: HashSet<K>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_chat_rate_limit() {
+        let mut moderation = Moderation::new();
+        let (key, now) = (Address::ZERO, Instant::now());
+
+        for _ in 0..CHAT_BURST as usize {
+            assert!(moderation.allow(key, now));
+        }
+        assert!(!moderation.allow(key, now));
+
+        // Tokens refill over time
+        let later = now + Duration::from_secs_f64(1.0 / CHAT_REFILL_RATE);
+        assert!(moderation.allow(key, later));
+        assert!(!moderation.allow(key, later));
+    }
+
+    #[test]
+    fn test_block_and_filter() {
+        let mut moderation = Moderation::new();
+        let (spammer, player) = (Address::ZERO, Address::repeat_byte(1));
+
+        moderation.block(spammer, true);
+        moderation.set_filter(ChatFilter::new(|m| {
+            (!m.contains("spam")).then(|| m.replace("heck", "****"))
+        }));
+
+        assert_eq!(moderation.moderate(&spammer, "gm"), None);
+        assert_eq!(moderation.moderate(&player, "buy spam"), None);
+        assert_eq!(
+            moderation.moderate(&player, "what the heck"),
+            Some("what the ****".to_string())
+        );
+    }
+}
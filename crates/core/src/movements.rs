@@ -1,7 +1,8 @@
 use crate::prelude::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::ops::AddAssign;
 
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct Position<T = i32> {
     pub x: T,
     pub y: T,
@@ -13,6 +14,20 @@ impl<T> Position<T> {
     }
 }
 
+impl Position<i32> {
+    /// Checks that the delta is a single step along one axis
+    pub fn is_step(&self) -> bool {
+        matches!((self.x, self.y), (-1 | 1, 0) | (0, -1 | 1))
+    }
+
+    /// Adds a delta, none on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let x = self.x.checked_add(other.x)?;
+        let y = self.y.checked_add(other.y)?;
+        Some(Self { x, y })
+    }
+}
+
 impl<T: AddAssign> AddAssign for Position<T> {
     fn add_assign(&mut self, other: Self) {
         self.x += other.x;
@@ -38,3 +53,21 @@ where
         self.y.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_single_steps_are_moves() {
+        assert!(Position::new(0, -1).is_step());
+        assert!(!Position::new(1, 1).is_step());
+        assert!(!Position::new(0, 0).is_step());
+        assert!(!Position::new(i32::MAX, 0).is_step());
+        assert!(!Position::new(i32::MIN, 0).is_step());
+        assert_eq!(
+            Position::new(i32::MAX, 0).checked_add(Position::new(1, 0)),
+            None
+        );
+    }
+}
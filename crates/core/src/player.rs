@@ -1,9 +1,11 @@
 use crate::movements::Position;
 use game_primitives::{Identifier, Player};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Character<N, B, T> {
+    #[schemars(with = "String")]
     pub name: N,
     pub balance: B,
     pub position: Position<T>,
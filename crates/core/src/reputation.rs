@@ -0,0 +1,426 @@
+use crate::prelude::*;
+use crate::work::unix_time;
+use game_network::NetworkCommand;
+use game_network::prelude::PeerId;
+use game_primitives::reputation::{BanEntry, BanKind};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::Path;
+use std::time::Duration;
+
+/// Default path to the persisted ban list
+pub const BANS_PATH: &str = "./bans.json";
+/// Score at which a sender gets banned
+const BAN_THRESHOLD: i32 = -100;
+/// Highest score a sender can earn
+const MAX_SCORE: i32 = 100;
+/// Duration of a ban in seconds
+pub const BAN_DURATION: u64 = 60 * 60;
+/// Messages allowed per sender within a second
+const MAX_MESSAGES_PER_SECOND: u32 = 50;
+/// Sequence numbers below the highest one still accepted out of order
+const REPLAY_WINDOW: u64 = 64;
+
+/// Offense
+///
+/// Misbehaviour lowering the reputation of a sender
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offense {
+    InvalidSignature,
+    InvalidShare,
+    Replay,
+    Spam,
+    Malformed,
+}
+
+impl Offense {
+    /// Name of the offense
+    pub fn name(&self) -> &'static str {
+        match self {
+            Offense::InvalidSignature => "invalid_signature",
+            Offense::InvalidShare => "invalid_share",
+            Offense::Replay => "replay",
+            Offense::Spam => "spam",
+            Offense::Malformed => "malformed",
+        }
+    }
+
+    /// Score removed by the offense
+    pub fn penalty(&self) -> i32 {
+        match self {
+            Offense::InvalidSignature => 50,
+            Offense::Malformed => 30,
+            Offense::Replay => 30,
+            Offense::InvalidShare => 20,
+            Offense::Spam => 10,
+        }
+    }
+}
+
+/// Score
+///
+/// Reputation of a single sender
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Score {
+    pub value: i32,
+    pub banned_until: u64,
+    /// Rate limit window (second, messages)
+    #[serde(skip)]
+    window: (u64, u32),
+}
+
+/// Scores
+///
+/// Reputation of the senders identified by `K`
+#[derive(Debug)]
+pub struct Scores<K> {
+    scores: HashMap<K, Score>,
+}
+
+impl<K> Default for Scores<K> {
+    fn default() -> Self {
+        let scores = Default::default();
+        Self { scores }
+    }
+}
+
+impl<K: Eq + Hash + Copy> Scores<K> {
+    /// Gets the score of a sender
+    pub fn get(&self, key: &K) -> Score {
+        self.scores.get(key).copied().unwrap_or_default()
+    }
+
+    /// Checks if the sender is banned
+    pub fn is_banned(&self, key: &K, now: u64) -> bool {
+        self.get(key).banned_until > now
+    }
+
+    /// Rewards a valid message
+    ///
+    /// Returns the new score
+    pub fn reward(&mut self, key: K) -> i32 {
+        let score = self.scores.entry(key).or_default();
+        score.value = (score.value + 1).min(MAX_SCORE);
+        score.value
+    }
+
+    /// Penalizes an offense
+    ///
+    /// Returns true if the sender got banned
+    pub fn penalize(&mut self, key: K, offense: Offense, now: u64) -> bool {
+        let score = self.scores.entry(key).or_default();
+        score.value = score.value.saturating_sub(offense.penalty());
+
+        if score.value > BAN_THRESHOLD || score.banned_until > now {
+            return false;
+        }
+
+        // Repeated offenders get banned again faster
+        score.banned_until = now.saturating_add(BAN_DURATION);
+        score.value = BAN_THRESHOLD / 2;
+        true
+    }
+
+    /// Counts a message against the rate limit
+    ///
+    /// Returns false if the sender exceeded the limit
+    pub fn record_message(&mut self, key: K, now: u64) -> bool {
+        let score = self.scores.entry(key).or_default();
+        match score.window {
+            (second, ref mut count) if second == now => *count += 1,
+            _ => score.window = (now, 1),
+        }
+
+        score.window.1 <= MAX_MESSAGES_PER_SECOND
+    }
+
+    /// Bans a sender until the given unix time
+    pub fn ban(&mut self, key: K, banned_until: u64) {
+        self.scores.entry(key).or_default().banned_until = banned_until;
+    }
+
+    /// Gets the active bans
+    pub fn bans(&self, now: u64) -> impl Iterator<Item = (&K, &Score)> {
+        self.scores
+            .iter()
+            .filter(move |(_, s)| s.banned_until > now)
+    }
+}
+
+/// Replay Window
+///
+/// Sequence numbers recently seen from an address
+/// Tolerates gossip delivered out of order, within the window
+#[derive(Debug, Clone, Copy, Default)]
+struct ReplayWindow {
+    highest: u64,
+    /// Bit `n` is set once `highest - n` is seen
+    seen: u64,
+}
+
+impl ReplayWindow {
+    /// Records a sequence number
+    ///
+    /// Returns false if it was already seen or is too old
+    fn record(&mut self, sequence: u64) -> bool {
+        if sequence > self.highest {
+            let shift = sequence - self.highest;
+            self.seen = if shift < REPLAY_WINDOW {
+                self.seen << shift
+            } else {
+                0
+            };
+            self.seen |= 1;
+            self.highest = sequence;
+            return true;
+        }
+
+        let offset = self.highest - sequence;
+        if offset >= REPLAY_WINDOW || self.seen & (1 << offset) != 0 {
+            return false;
+        }
+        self.seen |= 1 << offset;
+        true
+    }
+}
+
+/// Reputation
+///
+/// Tracks the behaviour of peers and addresses
+#[derive(Debug, Default)]
+pub struct Reputation {
+    pub peers: Scores<PeerId>,
+    pub addresses: Scores<Address>,
+    authors: HashMap<Address, PeerId>,
+    sequences: HashMap<Address, ReplayWindow>,
+    commands: Vec<NetworkCommand>,
+    changed: bool,
+}
+
+impl Reputation {
+    /// Creates a new reputation tracker
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Records a message of the address, signed for the peer publishing it
+    ///
+    /// Returns false if its sequence number was already seen
+    pub fn observe(&mut self, address: Address, peer: PeerId, sequence: u64) -> bool {
+        self.authors.insert(address, peer);
+        self.sequences.entry(address).or_default().record(sequence)
+    }
+
+    /// Checks if the sender is banned
+    pub fn is_banned(&self, address: Option<&Address>, peer: Option<&PeerId>) -> bool {
+        let now = unix_time();
+        address.is_some_and(|a| self.addresses.is_banned(a, now))
+            || peer.is_some_and(|p| self.peers.is_banned(p, now))
+    }
+
+    /// Rewards a valid message of the sender
+    pub fn reward(&mut self, address: Address, peer: Option<PeerId>) {
+        self.addresses.reward(address);
+
+        // Let gossipsub know once a peer recovers
+        if let Some(peer) = peer
+            && self.peers.get(&peer).value < 0
+        {
+            let score = self.peers.reward(peer);
+            self.commands
+                .push(NetworkCommand::Score(peer, score as f64));
+        }
+    }
+
+    /// Penalizes an offense of the sender
+    ///
+    /// The peer defaults to the last one signed for by the address
+    pub fn penalize(&mut self, address: Option<Address>, peer: Option<PeerId>, offense: Offense) {
+        let now = unix_time();
+        let peer = peer.or_else(|| address.and_then(|a| self.authors.get(&a).copied()));
+
+        if let Some(address) = address
+            && self.addresses.penalize(address, offense, now)
+        {
+            warn!(%address, offense = offense.name(), "Banned address");
+            self.changed = true;
+        }
+
+        if let Some(peer) = peer {
+            if self.peers.penalize(peer, offense, now) {
+                warn!(peer_id = %peer, offense = offense.name(), "Banned peer");
+                let duration = Duration::from_secs(BAN_DURATION);
+                self.commands.push(NetworkCommand::Ban(peer, duration));
+                self.changed = true;
+            }
+
+            let score = self.peers.get(&peer).value;
+            self.commands
+                .push(NetworkCommand::Score(peer, score as f64));
+        }
+    }
+
+    /// Bans a peer on behalf of the operator
+    pub fn ban_peer(&mut self, peer: PeerId, duration: Duration) {
+        warn!(peer_id = %peer, duration = duration.as_secs(), "Banned peer by the operator");
+        self.peers
+            .ban(peer, unix_time().saturating_add(duration.as_secs()));
+        self.commands.push(NetworkCommand::Ban(peer, duration));
+        self.changed = true;
+    }
+
+    /// Drains the commands to apply on the network
+    pub fn drain_commands(&mut self) -> Vec<NetworkCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Gets the active bans
+    pub fn bans(&self) -> Vec<BanEntry> {
+        let now = unix_time();
+        let peers = self.peers.bans(now).map(|(peer, score)| BanEntry {
+            identifier: peer.to_string(),
+            kind: BanKind::Peer,
+            score: score.value,
+            banned_until: score.banned_until,
+        });
+        let addresses = self.addresses.bans(now).map(|(address, score)| BanEntry {
+            identifier: address.to_string(),
+            kind: BanKind::Address,
+            score: score.value,
+            banned_until: score.banned_until,
+        });
+
+        peers.chain(addresses).collect()
+    }
+
+    /// Saves the ban list if it changed
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        if !std::mem::take(&mut self.changed) {
+            return Ok(());
+        }
+
+        let now = unix_time();
+        let list = BanList {
+            peers: self
+                .peers
+                .bans(now)
+                .map(|(peer, score)| (peer.to_string(), *score))
+                .collect(),
+            addresses: self
+                .addresses
+                .bans(now)
+                .map(|(address, score)| (*address, *score))
+                .collect(),
+        };
+
+        std::fs::write(path, serde_json::to_string_pretty(&list)?)?;
+        Ok(())
+    }
+
+    /// Loads the ban list
+    ///
+    /// Queues the remaining peer bans for the network
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let list: BanList = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let mut reputation = Self::new();
+        let now = unix_time();
+
+        for (peer, score) in list.peers {
+            let peer = peer.parse::<PeerId>()?;
+            let remaining = score.banned_until.saturating_sub(now);
+            if remaining > 0 {
+                reputation.peers.ban(peer, score.banned_until);
+                let duration = Duration::from_secs(remaining);
+                reputation
+                    .commands
+                    .push(NetworkCommand::Ban(peer, duration));
+            }
+        }
+        for (address, score) in list.addresses {
+            reputation.addresses.ban(address, score.banned_until);
+        }
+
+        Ok(reputation)
+    }
+}
+
+/// Ban List
+///
+/// Persisted form of the active bans
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BanList {
+    peers: HashMap<String, Score>,
+    addresses: HashMap<Address, Score>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ban_after_offenses() {
+        let mut scores = Scores::default();
+        let key = Address::ZERO;
+
+        // Bans once the threshold is reached
+        assert!(!scores.penalize(key, Offense::InvalidSignature, 0));
+        assert!(scores.penalize(key, Offense::InvalidSignature, 0));
+        assert!(scores.is_banned(&key, BAN_DURATION - 1));
+        assert!(!scores.is_banned(&key, BAN_DURATION));
+
+        // Offenses while banned keep lowering the score, down to the floor
+        scores.scores.get_mut(&key).unwrap().value = i32::MIN;
+        assert!(!scores.penalize(key, Offense::InvalidSignature, 1));
+        assert_eq!(scores.get(&key).value, i32::MIN);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut scores = Scores::default();
+        let key = Address::ZERO;
+
+        for _ in 0..MAX_MESSAGES_PER_SECOND {
+            assert!(scores.record_message(key, 1));
+        }
+        assert!(!scores.record_message(key, 1));
+
+        // Next second starts a new window
+        assert!(scores.record_message(key, 2));
+    }
+
+    #[test]
+    fn test_peer_ban_commands() {
+        let mut reputation = Reputation::new();
+        let (address, peer) = (Address::ZERO, PeerId::random());
+
+        // Address offenses are attributed to its peer
+        assert!(reputation.observe(address, peer, 1));
+        assert!(!reputation.observe(address, peer, 1));
+        reputation.penalize(Some(address), None, Offense::InvalidSignature);
+        reputation.penalize(Some(address), None, Offense::InvalidSignature);
+
+        let commands = reputation.drain_commands();
+        assert!(
+            commands
+                .iter()
+                .any(|c| matches!(c, NetworkCommand::Ban(p, _) if *p == peer))
+        );
+        assert!(reputation.is_banned(Some(&address), Some(&peer)));
+        assert_eq!(reputation.bans().len(), 2);
+    }
+
+    #[test]
+    fn test_replay_window() {
+        let mut window = ReplayWindow::default();
+        assert!(window.record(100));
+        assert!(!window.record(100));
+
+        // Late messages are accepted once, within the window
+        assert!(window.record(98));
+        assert!(!window.record(98));
+        assert!(window.record(100 + REPLAY_WINDOW));
+        assert!(!window.record(100));
+        assert!(window.record(101));
+        assert!(!window.record(0));
+    }
+}
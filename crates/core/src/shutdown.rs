@@ -0,0 +1,26 @@
+use crate::prelude::*;
+use std::time::Duration;
+
+/// Time given to in-flight claim transactions before exiting
+pub const CLAIM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Waits for a termination signal
+///
+/// Resolves on SIGINT or SIGTERM
+pub async fn signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {},
+        }
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    info!("Received shutdown signal");
+    Ok(())
+}
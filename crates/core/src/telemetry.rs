@@ -0,0 +1,99 @@
+use crate::prelude::{Deserialize, Serialize};
+use opentelemetry::Context;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Trace Context
+///
+/// Span context carried across the gossip hop
+/// Absent when the sender does not export traces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub flags: u8,
+}
+
+impl TraceContext {
+    /// Gets the context of the current span
+    pub fn current() -> Option<Self> {
+        Self::of(&Span::current())
+    }
+
+    /// Gets the context of a span, if it is exported
+    pub fn of(span: &Span) -> Option<Self> {
+        let context = span.context();
+        let otel_span = context.span();
+        let span_context = otel_span.span_context();
+        span_context.is_valid().then(|| Self {
+            trace_id: span_context.trace_id().to_bytes(),
+            span_id: span_context.span_id().to_bytes(),
+            flags: span_context.trace_flags().to_u8(),
+        })
+    }
+
+    /// Makes the remote span the parent of a span
+    ///
+    /// Must be called before the span is entered
+    pub fn attach(&self, span: &Span) {
+        span.set_parent(Context::new().with_remote_span_context(self.span_context()));
+    }
+
+    /// Links a span to the remote span, for spans with many causes
+    pub fn link(&self, span: &Span) {
+        span.add_link(self.span_context());
+    }
+
+    /// Gets the OpenTelemetry span context
+    fn span_context(&self) -> SpanContext {
+        SpanContext::new(
+            TraceId::from_bytes(self.trace_id),
+            SpanId::from_bytes(self.span_id),
+            TraceFlags::new(self.flags),
+            true,
+            TraceState::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BincodeHelper;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing::info_span;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_context_crosses_the_gossip_hop() -> anyhow::Result<()> {
+        // Spans are not exported without a tracer
+        tracing::subscriber::with_default(tracing_subscriber::registry(), || {
+            assert!(TraceContext::of(&info_span!("untraced")).is_none());
+        });
+
+        let provider = SdkTracerProvider::builder().build();
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("test"));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let sender = info_span!("send_signed");
+            let trace = TraceContext::of(&sender).unwrap();
+
+            // Round trip through the envelope encoding
+            let encoded = BincodeHelper::encode(&trace)?;
+            let decoded = BincodeHelper::decode::<TraceContext>(&encoded)?;
+            assert_eq!(decoded, trace);
+
+            let receiver = info_span!("receive");
+            decoded.attach(&receiver);
+            let parent = receiver.context();
+            assert_eq!(
+                parent.span().span_context().trace_id().to_bytes(),
+                trace.trace_id
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,433 @@
+use crate::prelude::*;
+use crate::world::Position;
+use game_primitives::WorldMap;
+use game_primitives::map::MapTile;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+/// Tiled flip flags stored in the upper bits of a tile gid
+const FLIP_FLAGS: u32 = 0xF000_0000;
+/// Layer property marking a tile layer as blocking
+const COLLISION_PROPERTY: &str = "collision";
+/// Object type marking a spawn point
+const SPAWN_TYPE: &str = "spawn";
+/// Object type marking a named zone
+const ZONE_TYPE: &str = "zone";
+/// Upper nonce bits reserved for the zone index
+pub const ZONE_NONCE_BITS: usize = 192;
+
+/// Tile Map
+///
+/// World map loaded from a Tiled JSON file
+/// Position `(0, 0)` is the bottom-left tile of the map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "tilewidth")]
+    pub tile_width: u32,
+    #[serde(rename = "tileheight")]
+    pub tile_height: u32,
+    pub layers: Vec<Layer>,
+    pub tilesets: Vec<Tileset>,
+    /// Directory of the map file, used to resolve tileset images
+    #[serde(skip)]
+    pub directory: PathBuf,
+    /// Named zones, read from the objects once loaded
+    #[serde(skip)]
+    zones: Vec<Zone>,
+}
+
+/// Map Layer
+///
+/// Either a grid of tiles or a group of objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Layer {
+    TileLayer(TileLayer),
+    ObjectGroup(ObjectGroup),
+}
+
+/// Tile Layer
+///
+/// Row-major grid of tile gids, `0` being an empty tile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileLayer {
+    pub name: String,
+    pub data: Vec<u32>,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+/// Object Group
+///
+/// Holds spawn points and zones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectGroup {
+    pub name: String,
+    pub objects: Vec<MapObject>,
+}
+
+/// Map Object
+///
+/// Rectangle placed on the map, in pixels from the top-left corner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapObject {
+    pub name: String,
+    #[serde(rename = "type", alias = "class", default)]
+    pub kind: String,
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+/// Custom Property
+///
+/// Key-value pair attached to layers and objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Property {
+    pub name: String,
+    pub value: serde_json::Value,
+}
+
+/// Tileset
+///
+/// Embedded tileset backed by a single image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tileset {
+    #[serde(rename = "firstgid")]
+    pub first_gid: u32,
+    pub image: String,
+    pub columns: u32,
+    #[serde(rename = "tilecount")]
+    pub tile_count: u32,
+}
+
+/// Zone
+///
+/// Named area of the map, in tile positions
+/// Each zone mines its own disjoint nonce range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Zone {
+    /// Order of the zone within the map
+    pub index: usize,
+    pub name: String,
+    /// Bottom-left tile of the zone
+    pub min: Position,
+    /// Top-right tile of the zone
+    pub max: Position,
+}
+
+impl Zone {
+    /// Checks if the position is inside the zone
+    pub fn contains(&self, position: &Position) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+    }
+
+    /// Gets the nonce range mined within the zone
+    pub fn nonce_range(&self) -> Range<U256> {
+        let start = U256::from(self.index) << ZONE_NONCE_BITS;
+        let end = U256::from(self.index + 1) << ZONE_NONCE_BITS;
+
+        start..end
+    }
+}
+
+impl TileMap {
+    /// Loads a map from a Tiled JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let mut map: Self = serde_json::from_str(&content)?;
+
+        // Validate layer sizes
+        let size = (map.width as usize)
+            .checked_mul(map.height as usize)
+            .ok_or(anyhow!(
+                "Map size {}x{} is too large",
+                map.width,
+                map.height
+            ))?;
+        for layer in map.tile_layers() {
+            if layer.data.len() != size {
+                return Err(anyhow!("Layer {} has invalid size", layer.name));
+            }
+        }
+
+        // Validate tileset grids
+        if let Some(tileset) = map.tilesets.iter().find(|tileset| tileset.columns == 0) {
+            return Err(anyhow!("Tileset {} has no columns", tileset.image));
+        }
+
+        map.directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        map.zones = map.read_zones();
+        Ok(map)
+    }
+
+    /// Gets the tile layers of the map
+    pub fn tile_layers(&self) -> impl Iterator<Item = &TileLayer> {
+        self.layers.iter().filter_map(|layer| match layer {
+            Layer::TileLayer(l) => Some(l),
+            Layer::ObjectGroup(_) => None,
+        })
+    }
+
+    /// Gets the objects of the map
+    pub fn objects(&self) -> impl Iterator<Item = &MapObject> {
+        self.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::ObjectGroup(g) => Some(g.objects.iter()),
+                Layer::TileLayer(_) => None,
+            })
+            .flatten()
+    }
+
+    /// Gets the tile index of a position, if inside the map
+    fn tile_index(&self, position: &Position) -> Option<usize> {
+        let (x, y) = (
+            u32::try_from(position.x).ok()?,
+            u32::try_from(position.y).ok()?,
+        );
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        // Tiled rows start from the top
+        let row = self.height - 1 - y;
+        Some((row * self.width + x) as usize)
+    }
+
+    /// Converts a pixel point of an object into a tile position
+    fn object_position(&self, x: f64, y: f64) -> Position {
+        let column = (x / self.tile_width as f64).floor() as i32;
+        let row = (y / self.tile_height as f64).floor() as i32;
+
+        Position::new(column, self.height as i32 - 1 - row)
+    }
+
+    /// Checks if a player can stand on the position
+    pub fn is_walkable(&self, position: &Position) -> bool {
+        let Some(index) = self.tile_index(position) else {
+            return false;
+        };
+
+        // Any tile on a collision layer blocks
+        !self
+            .tile_layers()
+            .filter(|layer| layer.has_property(COLLISION_PROPERTY))
+            .any(|layer| layer.data[index] & !FLIP_FLAGS != 0)
+    }
+
+    /// Gets the spawn points of the map
+    pub fn spawn_points(&self) -> Vec<Position> {
+        self.objects()
+            .filter(|object| object.kind == SPAWN_TYPE)
+            .map(|object| self.object_position(object.x, object.y))
+            .collect()
+    }
+
+    /// Picks a spawn point for the given key
+    ///
+    /// Same key always resolves to the same spawn point
+    pub fn spawn_point(&self, key: &[u8]) -> Position {
+        let spawns = self.spawn_points();
+        if spawns.is_empty() {
+            return Position::default();
+        }
+
+        let seed = key
+            .iter()
+            .fold(0usize, |acc, b| acc.wrapping_add(*b as usize));
+        spawns[seed % spawns.len()]
+    }
+
+    /// Gets the named zones of the map
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    /// Reads the named zones from the map objects
+    fn read_zones(&self) -> Vec<Zone> {
+        self.objects()
+            .filter(|object| object.kind == ZONE_TYPE)
+            .enumerate()
+            .map(|(index, object)| {
+                // Object rectangles grow downwards in pixels
+                let top_left = self.object_position(object.x, object.y);
+                let right = (object.x + object.width - 1.0).max(object.x);
+                let bottom = (object.y + object.height - 1.0).max(object.y);
+                let bottom_right = self.object_position(right, bottom);
+
+                Zone {
+                    index,
+                    name: object.name.clone(),
+                    min: Position::new(top_left.x, bottom_right.y),
+                    max: Position::new(bottom_right.x, top_left.y),
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the zone the position is in
+    pub fn zone_at(&self, position: &Position) -> Option<Zone> {
+        self.zones
+            .iter()
+            .find(|zone| zone.contains(position))
+            .cloned()
+    }
+
+    /// Gets the zone a mined nonce belongs to
+    pub fn zone_of_nonce(&self, nonce: U256) -> Option<Zone> {
+        let index = usize::try_from(nonce >> ZONE_NONCE_BITS).ok()?;
+        self.zones.get(index).cloned()
+    }
+
+    /// Gets the tileset and local index of a tile gid
+    fn tileset_of(&self, gid: u32) -> Option<(&Tileset, u32)> {
+        self.tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)
+            .map(|tileset| (tileset, gid - tileset.first_gid))
+            .filter(|(tileset, index)| *index < tileset.tile_count)
+    }
+
+    /// Resolves a tileset image relative to the map file
+    fn image_path(&self, image: &str) -> String {
+        let mut path = PathBuf::new();
+        for component in self.directory.join(image).components() {
+            match component {
+                Component::ParentDir => {
+                    path.pop();
+                }
+                c => path.push(c),
+            }
+        }
+
+        path.to_string_lossy().into_owned()
+    }
+}
+
+impl TileLayer {
+    /// Checks if a boolean property is set on the layer
+    pub fn has_property(&self, name: &str) -> bool {
+        self.properties
+            .iter()
+            .any(|p| p.name == name && p.value.as_bool().unwrap_or_default())
+    }
+}
+
+impl WorldMap for TileMap {
+    fn tile_size(&self) -> (u32, u32) {
+        (self.tile_width, self.tile_height)
+    }
+
+    fn tiles(&self) -> Vec<MapTile> {
+        let mut tiles = Vec::new();
+
+        for (layer, tile_layer) in self.tile_layers().enumerate() {
+            if !tile_layer.visible {
+                continue;
+            }
+
+            for (i, gid) in tile_layer.data.iter().enumerate() {
+                let Some((tileset, index)) = self.tileset_of(gid & !FLIP_FLAGS) else {
+                    continue;
+                };
+
+                let (column, row) = (i as u32 % self.width, i as u32 / self.width);
+                tiles.push(MapTile {
+                    position: (column as i32, (self.height - 1 - row) as i32),
+                    layer,
+                    tileset: self.image_path(&tileset.image),
+                    index,
+                    grid: (
+                        tileset.columns,
+                        tileset.tile_count.div_ceil(tileset.columns),
+                    ),
+                });
+            }
+        }
+
+        tiles
+    }
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP_PATH: &str = "../../assets/maps/world.json";
+
+    #[test]
+    fn test_load_world_map() -> Result<()> {
+        let map = TileMap::load(MAP_PATH)?;
+
+        assert!(!map.spawn_points().is_empty());
+        assert!(!map.zones().is_empty());
+        assert!(!map.tiles().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_collisions() -> Result<()> {
+        let map = TileMap::load(MAP_PATH)?;
+
+        // Borders and outside of the map are blocked
+        assert!(!map.is_walkable(&Position::new(0, 0)));
+        assert!(!map.is_walkable(&Position::new(-1, 5)));
+        assert!(!map.is_walkable(&Position::new(map.width as i32, 5)));
+
+        // Spawn points are walkable
+        for spawn in map.spawn_points() {
+            assert!(map.is_walkable(&spawn));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_zones_cover_spawns() -> Result<()> {
+        let map = TileMap::load(MAP_PATH)?;
+
+        for spawn in map.spawn_points() {
+            assert!(map.zone_at(&spawn).is_some());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_zone_nonce_ranges() -> Result<()> {
+        let map = TileMap::load(MAP_PATH)?;
+        let zones = map.zones();
+
+        // Ranges are disjoint and map back to their zone
+        for pair in zones.windows(2) {
+            assert_eq!(pair[0].nonce_range().end, pair[1].nonce_range().start);
+        }
+        for zone in zones {
+            let range = zone.nonce_range();
+            assert_eq!(map.zone_of_nonce(range.start).as_ref(), Some(zone));
+            assert_eq!(
+                map.zone_of_nonce(range.end - U256::ONE).as_ref(),
+                Some(zone)
+            );
+        }
+
+        let outside = U256::from(zones.len()) << ZONE_NONCE_BITS;
+        assert!(map.zone_of_nonce(outside).is_none());
+        Ok(())
+    }
+}
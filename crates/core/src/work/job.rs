@@ -0,0 +1,75 @@
+use crate::BincodeHelper;
+use crate::prelude::*;
+use game_contract::prelude::{B256, keccak256};
+use std::ops::Range;
+
+/// Job
+///
+/// Nonce space mined by the peers, split into leasable chunks
+/// The params are opaque to the pool and only read by the backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: B256,
+    pub backend: String,
+    pub params: Vec<u8>,
+    pub range: Range<U256>,
+    pub chunk_size: U256,
+}
+
+impl Job {
+    /// Creates a new job
+    pub fn new(
+        backend: impl Into<String>,
+        params: Vec<u8>,
+        range: Range<U256>,
+        chunk_size: U256,
+    ) -> Result<Self> {
+        let backend = backend.into();
+        let id = Self::hash(&backend, &params, &range, chunk_size)?;
+
+        Ok(Self {
+            id,
+            backend,
+            params,
+            range,
+            chunk_size,
+        })
+    }
+
+    /// Hashes the job content into its identifier
+    fn hash(backend: &str, params: &[u8], range: &Range<U256>, chunk: U256) -> Result<B256> {
+        let encoded = BincodeHelper::encode(&(backend, params, range, chunk))?;
+        Ok(keccak256(encoded))
+    }
+
+    /// Verifies the job identifier and chunking
+    pub fn verify(&self) -> Result<()> {
+        if self.chunk_size.is_zero() || self.range.is_empty() {
+            return Err(anyhow!("Job {} has an empty nonce space", self.id));
+        }
+
+        let id = Self::hash(&self.backend, &self.params, &self.range, self.chunk_size)?;
+        if id != self.id {
+            return Err(anyhow!("Job {} does not match its content", self.id));
+        }
+
+        Ok(())
+    }
+
+    /// Gets the number of chunks in the job
+    pub fn chunks(&self) -> U256 {
+        let size = self.range.end - self.range.start;
+        size.div_ceil(self.chunk_size)
+    }
+
+    /// Gets the nonce range of a chunk
+    pub fn chunk_range(&self, chunk: U256) -> Option<Range<U256>> {
+        if chunk >= self.chunks() {
+            return None;
+        }
+
+        let start = self.range.start + chunk * self.chunk_size;
+        let end = (start + self.chunk_size).min(self.range.end);
+        Some(start..end)
+    }
+}
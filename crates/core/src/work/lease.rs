@@ -0,0 +1,175 @@
+use crate::prelude::*;
+use crate::work::{JOB_TTL, Job, LEASE_TTL, MAX_JOBS};
+use game_contract::prelude::B256;
+use std::collections::{HashMap, HashSet};
+
+/// Lease
+///
+/// Claim of a peer on a single chunk of a job
+/// Holder is the signer of the message carrying the lease
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Lease {
+    pub job: B256,
+    pub chunk: U256,
+    pub expires_at: u64,
+}
+
+/// Lease Table
+///
+/// Tracks the known jobs and the holder of each leased chunk
+/// Jobs left unused for a while are forgotten with their chunks
+#[derive(Debug, Default)]
+pub struct LeaseTable {
+    jobs: HashMap<B256, (Job, u64)>,
+    holders: HashMap<(B256, U256), (Address, u64)>,
+    completed: HashSet<(B256, U256)>,
+}
+
+impl LeaseTable {
+    /// Creates a new lease table
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a job to the table
+    ///
+    /// Returns true if the job was not known
+    pub fn announce(&mut self, job: Job, now: u64) -> Result<bool> {
+        if let Some((_, seen)) = self.jobs.get_mut(&job.id) {
+            *seen = now;
+            return Ok(false);
+        }
+
+        job.verify()?;
+        if self.jobs.len() >= MAX_JOBS {
+            return Err(anyhow!("Too many jobs to track {}", job.id));
+        }
+        self.jobs.insert(job.id, (job, now));
+        Ok(true)
+    }
+
+    /// Gets a known job
+    pub fn job(&self, id: &B256) -> Option<&Job> {
+        self.jobs.get(id).map(|(job, _)| job)
+    }
+
+    /// Applies a lease claimed or renewed by the holder
+    ///
+    /// Conflicting claims on an active chunk are won by the lower address
+    pub fn apply(&mut self, holder: Address, lease: &Lease, now: u64) -> Result<()> {
+        let (job, seen) = self
+            .jobs
+            .get_mut(&lease.job)
+            .ok_or(anyhow!("Unknown job {}", lease.job))?;
+
+        // Validate the lease
+        if lease.chunk >= job.chunks() {
+            return Err(anyhow!("Chunk {} is out of job range", lease.chunk));
+        }
+        if lease.expires_at <= now || lease.expires_at > now + 2 * LEASE_TTL {
+            return Err(anyhow!("Lease expiry {} is invalid", lease.expires_at));
+        }
+
+        // Resolve conflicts with the active holder
+        let key = (lease.job, lease.chunk);
+        if let Some((current, expires_at)) = self.holders.get(&key)
+            && *current != holder
+            && *expires_at > now
+            && *current < holder
+        {
+            return Err(anyhow!("Chunk {} is leased by {current}", lease.chunk));
+        }
+
+        *seen = now;
+        self.holders.insert(key, (holder, lease.expires_at));
+        Ok(())
+    }
+
+    /// Releases a lease held by the holder
+    pub fn release(&mut self, holder: &Address, lease: &Lease) {
+        let key = (lease.job, lease.chunk);
+        if self.holders.get(&key).is_some_and(|(h, _)| h == holder) {
+            self.holders.remove(&key);
+        }
+    }
+
+    /// Releases every lease held by the holder
+    pub fn release_all(&mut self, holder: &Address) {
+        self.holders.retain(|_, (h, _)| h != holder);
+    }
+
+    /// Marks the chunk of a lease as fully mined
+    pub fn complete(&mut self, lease: &Lease) {
+        self.completed.insert((lease.job, lease.chunk));
+    }
+
+    /// Removes the expired leases and the jobs no longer used
+    ///
+    /// Returns the number of chunks made available again
+    pub fn prune(&mut self, now: u64) -> usize {
+        let before = self.holders.len();
+        self.holders.retain(|_, (_, expires_at)| *expires_at > now);
+
+        // Jobs without leases for a while are forgotten
+        let holders = &self.holders;
+        self.jobs.retain(|id, (_, seen)| {
+            seen.saturating_add(JOB_TTL) > now || holders.keys().any(|(job, _)| job == id)
+        });
+        let jobs = &self.jobs;
+        self.completed.retain(|(job, _)| jobs.contains_key(job));
+
+        before - self.holders.len()
+    }
+
+    /// Gets the active holder of a chunk
+    pub fn holder(&self, job: &B256, chunk: U256, now: u64) -> Option<Address> {
+        self.holders
+            .get(&(*job, chunk))
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(holder, _)| *holder)
+    }
+
+    /// Checks if the holder leases the chunk a nonce belongs to
+    pub fn is_held_by(&self, holder: &Address, nonce: U256, now: u64) -> bool {
+        self.jobs
+            .values()
+            .map(|(job, _)| job)
+            .filter(|job| job.range.contains(&nonce))
+            .any(|job| {
+                let chunk = (nonce - job.range.start) / job.chunk_size;
+                self.holder(&job.id, chunk, now) == Some(*holder)
+            })
+    }
+
+    /// Finds the lowest chunk free to lease
+    ///
+    /// Chunks abandoned by expired leases are reassigned first
+    pub fn next_free(&self, job: &B256, now: u64) -> Option<U256> {
+        let job = self.job(job)?;
+        let mut leased = self
+            .holders
+            .iter()
+            .filter(|((id, _), (_, expires_at))| *id == job.id && *expires_at > now)
+            .map(|((_, chunk), _)| *chunk)
+            .chain(
+                self.completed
+                    .iter()
+                    .filter(|(id, _)| *id == job.id)
+                    .map(|(_, chunk)| *chunk),
+            )
+            .collect::<Vec<_>>();
+        leased.sort();
+        leased.dedup();
+
+        // First gap in the leased chunks
+        let mut chunk = U256::ZERO;
+        for leased in leased {
+            if leased != chunk {
+                break;
+            }
+            chunk += U256::ONE;
+        }
+
+        (chunk < job.chunks()).then_some(chunk)
+    }
+}
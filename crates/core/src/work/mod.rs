@@ -0,0 +1,190 @@
+mod job;
+mod lease;
+mod pool;
+mod shares;
+
+pub use job::Job;
+pub use lease::{Lease, LeaseTable};
+pub use pool::WorkPool;
+pub use shares::ShareLog;
+
+use crate::prelude::{Deserialize, Serialize};
+
+/// Lease time to live in seconds
+pub const LEASE_TTL: u64 = 60;
+/// Seconds a job is kept without any announce or lease
+pub const JOB_TTL: u64 = 10 * LEASE_TTL;
+/// Highest number of jobs tracked at once
+pub const MAX_JOBS: usize = 256;
+/// Nonce bits of a single leased chunk
+pub const CHUNK_BITS: usize = 32;
+
+/// Work Event
+///
+/// Used to distribute the nonce space of jobs between peers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkEvent {
+    /// Announces a job to mine
+    Job(Job),
+    /// Claims or renews a lease on a chunk
+    Lease(Lease),
+    /// Gives up a lease before it expires
+    Release(Lease),
+}
+
+/// Current unix time in seconds
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn job() -> Job {
+        let range = U256::ZERO..U256::from(100);
+        Job::new("test", vec![1, 2, 3], range, U256::from(10)).unwrap()
+    }
+
+    #[test]
+    fn test_job_chunks() {
+        let job = job();
+
+        assert!(job.verify().is_ok());
+        assert_eq!(job.chunks(), U256::from(10));
+        assert_eq!(
+            job.chunk_range(U256::from(9)),
+            Some(U256::from(90)..U256::from(100))
+        );
+        assert_eq!(job.chunk_range(U256::from(10)), None);
+    }
+
+    #[test]
+    fn test_tampered_job() {
+        let mut job = job();
+        job.params.push(4);
+
+        assert!(LeaseTable::new().announce(job, 0).is_err());
+    }
+
+    #[test]
+    fn test_lease_conflicts() -> Result<()> {
+        let (job, now) = (job(), 1_000);
+        let mut table = LeaseTable::new();
+        table.announce(job.clone(), now)?;
+
+        let low = Address::with_last_byte(1);
+        let high = Address::with_last_byte(2);
+        let lease = Lease {
+            job: job.id,
+            chunk: U256::ZERO,
+            expires_at: now + LEASE_TTL,
+        };
+
+        // Lower address wins the chunk
+        table.apply(low, &lease, now)?;
+        assert!(table.apply(high, &lease, now).is_err());
+        assert_eq!(table.holder(&job.id, U256::ZERO, now), Some(low));
+        assert_eq!(table.next_free(&job.id, now), Some(U256::ONE));
+
+        // Nonces of the chunk are only mined by its holder
+        assert!(table.is_held_by(&low, U256::from(9), now));
+        assert!(!table.is_held_by(&low, U256::from(10), now));
+        assert!(!table.is_held_by(&high, U256::from(9), now));
+        assert!(!table.is_held_by(&low, U256::from(9), now + LEASE_TTL));
+        Ok(())
+    }
+
+    #[test]
+    fn test_abandoned_lease() -> Result<()> {
+        let (job, now) = (job(), 1_000);
+        let mut table = LeaseTable::new();
+        table.announce(job.clone(), now)?;
+
+        // Lease two chunks, first one goes offline
+        for (chunk, holder) in [(0u8, 1u8), (1, 2)] {
+            let lease = Lease {
+                job: job.id,
+                chunk: U256::from(chunk),
+                expires_at: now + LEASE_TTL + chunk as u64,
+            };
+            table.apply(Address::with_last_byte(holder), &lease, now)?;
+        }
+
+        // Expired chunk is reassigned first
+        let later = now + LEASE_TTL;
+        assert_eq!(table.prune(later), 1);
+        assert_eq!(table.next_free(&job.id, later), Some(U256::ZERO));
+
+        // Unused jobs are forgotten
+        table.prune(later + JOB_TTL);
+        assert!(table.job(&job.id).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_lease_renewal() -> Result<()> {
+        let (job, now) = (job(), 1_000);
+        let holder = Address::with_last_byte(1);
+        let mut pool = WorkPool::new();
+
+        // First lease announces the job
+        let (range, events) = pool.lease(holder, job.clone(), now)?;
+        assert_eq!(range, U256::ZERO..U256::from(10));
+        assert_eq!(events.len(), 2);
+
+        // Lease is kept, then renewed close to expiry
+        assert!(pool.lease(holder, job.clone(), now + 1)?.1.is_empty());
+        let (_, events) = pool.lease(holder, job.clone(), now + LEASE_TTL / 2)?;
+        assert!(matches!(events[..], [WorkEvent::Lease(_)]));
+
+        // Completed chunks are not leased again
+        assert!(pool.complete(holder).is_some());
+        let (range, _) = pool.lease(holder, job, now + LEASE_TTL / 2)?;
+        assert_eq!(range, U256::from(10)..U256::from(20));
+        Ok(())
+    }
+
+    #[test]
+    fn test_share_log() {
+        let share = |nonce: u64, block| Share {
+            address: Address::ZERO,
+            nonce: U256::from(nonce),
+            target: Address::ZERO,
+            block,
+        };
+        let mut log = ShareLog::with_capacity(2);
+        assert!(log.insert(share(1, true), 0));
+        assert!(log.insert(share(2, false), 0));
+        assert!(!log.insert(share(2, false), 0));
+
+        // A looser target does not make a new share
+        let looser = Share {
+            target: Address::repeat_byte(0xff),
+            ..share(2, false)
+        };
+        assert!(!log.insert(looser, 0));
+
+        // Oldest shares are forgotten, blocks are not
+        assert!(log.insert(share(3, false), 3));
+        assert!(log.insert(share(4, false), 4));
+        assert!(!log.contains(&share(2, false)));
+        assert!(log.contains(&share(3, false)));
+        assert!(!log.insert(share(1, true), 0));
+
+        // Remembered shares of the miner, oldest first
+        assert_eq!(
+            log.shares_of(&Address::ZERO),
+            vec![
+                (U256::from(1), true, 0),
+                (U256::from(3), false, 3),
+                (U256::from(4), false, 4)
+            ]
+        );
+        assert!(log.shares_of(&Address::with_last_byte(1)).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::prelude::*;
+use crate::work::{Job, LEASE_TTL, Lease, LeaseTable, WorkEvent};
+use std::ops::Range;
+
+/// Work Pool
+///
+/// Local view of the network jobs and the lease held by this node
+#[derive(Debug, Default)]
+pub struct WorkPool {
+    pub table: LeaseTable,
+    lease: Option<Lease>,
+}
+
+impl WorkPool {
+    /// Creates a new work pool
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Gets the job of the current lease
+    pub fn current_job(&self) -> Option<&Job> {
+        self.lease.and_then(|lease| self.table.job(&lease.job))
+    }
+
+    /// Leases a chunk of the job for the local holder
+    ///
+    /// Returns the nonce range to mine and the events to broadcast
+    pub fn lease(
+        &mut self,
+        holder: Address,
+        job: Job,
+        now: u64,
+    ) -> Result<(Range<U256>, Vec<WorkEvent>)> {
+        let mut events = Vec::new();
+        self.table.prune(now);
+
+        // Announce unknown jobs
+        if self.table.announce(job.clone(), now)? {
+            events.push(WorkEvent::Job(job.clone()));
+        }
+
+        // Keep the current lease while it is still held
+        let current = self.lease.filter(|lease| {
+            lease.job == job.id && self.table.holder(&lease.job, lease.chunk, now) == Some(holder)
+        });
+        let lease = match current {
+            Some(lease) if lease.expires_at > now + LEASE_TTL / 2 => lease,
+            Some(lease) => Lease {
+                expires_at: now + LEASE_TTL,
+                ..lease
+            },
+            None => Lease {
+                job: job.id,
+                chunk: self
+                    .table
+                    .next_free(&job.id, now)
+                    .ok_or(anyhow!("Job {} has no free chunk", job.id))?,
+                expires_at: now + LEASE_TTL,
+            },
+        };
+
+        // Release the lease of a previous job
+        if let Some(previous) = self.lease.filter(|lease| lease.job != job.id) {
+            self.table.release(&holder, &previous);
+            events.push(WorkEvent::Release(previous));
+        }
+
+        // Claim or renew the lease
+        if self.lease != Some(lease) {
+            self.table.apply(holder, &lease, now)?;
+            events.push(WorkEvent::Lease(lease));
+            self.lease = Some(lease);
+        }
+
+        let range = job
+            .chunk_range(lease.chunk)
+            .ok_or(anyhow!("Chunk {} is out of job range", lease.chunk))?;
+        Ok((range, events))
+    }
+
+    /// Completes the current lease once its range is mined
+    ///
+    /// Returns the release event to broadcast
+    pub fn complete(&mut self, holder: Address) -> Option<WorkEvent> {
+        let lease = self.lease.take()?;
+        self.table.complete(&lease);
+        self.table.release(&holder, &lease);
+
+        Some(WorkEvent::Release(lease))
+    }
+
+    /// Applies a work event received from a peer
+    pub fn apply(&mut self, holder: Address, event: &WorkEvent, now: u64) -> Result<()> {
+        self.table.prune(now);
+        match event {
+            WorkEvent::Job(job) => self.table.announce(job.clone(), now).map(|_| ()),
+            WorkEvent::Lease(lease) => self.table.apply(holder, lease, now),
+            WorkEvent::Release(lease) => {
+                self.table.release(&holder, lease);
+                Ok(())
+            }
+        }
+    }
+}
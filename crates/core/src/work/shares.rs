@@ -0,0 +1,88 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Shares remembered to drop the duplicates
+pub const MAX_SHARES: usize = 100_000;
+
+/// Share Log
+///
+/// Shares already accounted by miner and nonce, the oldest ones forgotten first
+/// Blocks are kept for good, a replayed one would fail a whole claim batch
+#[derive(Debug)]
+pub struct ShareLog {
+    capacity: usize,
+    order: VecDeque<((Address, U256), u64)>,
+    seen: HashSet<(Address, U256)>,
+    blocks: HashMap<(Address, U256), u64>,
+}
+
+impl Default for ShareLog {
+    fn default() -> Self {
+        Self::with_capacity(MAX_SHARES)
+    }
+}
+
+impl ShareLog {
+    /// Creates a new share log
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a share log remembering up to `capacity` shares
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::new(),
+            seen: HashSet::new(),
+            blocks: HashMap::new(),
+        }
+    }
+
+    /// Checks if the nonce of the share was already accounted for its miner
+    ///
+    /// The target is left out, a looser one does not make a new share
+    pub fn contains(&self, share: &Share) -> bool {
+        let key = (share.address, share.nonce);
+        self.seen.contains(&key) || self.blocks.contains_key(&key)
+    }
+
+    /// Records a share accounted at a unix time in milliseconds
+    ///
+    /// Returns false if it was already accounted
+    pub fn insert(&mut self, share: Share, timestamp: u64) -> bool {
+        if self.contains(&share) {
+            return false;
+        }
+        let key = (share.address, share.nonce);
+        if share.block {
+            self.blocks.insert(key, timestamp);
+            return true;
+        }
+
+        if self.order.len() >= self.capacity
+            && let Some((oldest, _)) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.order.push_back((key, timestamp));
+        self.seen.insert(key)
+    }
+
+    /// Gets the remembered nonces of a miner, with their block flag and time, oldest first
+    pub fn shares_of(&self, miner: &Address) -> Vec<(U256, bool, u64)> {
+        let shares = self
+            .order
+            .iter()
+            .filter(|((address, _), _)| address == miner)
+            .map(|((_, nonce), timestamp)| (*nonce, false, *timestamp));
+        let blocks = self
+            .blocks
+            .iter()
+            .filter(|((address, _), _)| address == miner)
+            .map(|((_, nonce), timestamp)| (*nonce, true, *timestamp));
+
+        let mut shares = shares.chain(blocks).collect::<Vec<_>>();
+        shares.sort_by_key(|(nonce, _, timestamp)| (*timestamp, *nonce));
+        shares
+    }
+}
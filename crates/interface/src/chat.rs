@@ -1,15 +1,21 @@
 use crate::prelude::*;
 use bevy::prelude::*;
-use game_primitives::events::GameEvent;
+use game_primitives::events::{GameEvent, Traced};
+use game_primitives::message::{
+    ChatScope, ChatText, GLOBAL_CHANNEL, MAX_CHANNEL_LENGTH, MAX_MESSAGE_LENGTH,
+};
 
 /// System to handle chat input from keyboard
-pub fn handle_chat_input<F, Po>(
+pub fn handle_chat_input<W, F, Po, J>(
     keyboard_input: ResMut<ButtonInput<KeyCode>>,
     mut chat_input: ResMut<ChatInputText>,
-    sender: Res<KeyEventSender<F, Po>>,
+    sender: Res<KeyEventSender<F, Po, J>>,
+    world_state: Res<WorldStateResource<W>>,
 ) where
+    W: WorldState + Sync + Send + 'static,
     F: Send + Sync + 'static,
     Po: Send + Sync + 'static,
+    J: Send + Sync + 'static,
 {
     // Toggle chat input with Enter key
     if keyboard_input.just_pressed(KeyCode::Enter) {
@@ -25,7 +31,10 @@ pub fn handle_chat_input<F, Po>(
             // Send to network (this would need to be implemented with the network layer)
             // For now, we'll just add it to local messages
             debug!("Sending chat message: {message}");
-            if let Err(e) = sender.0.send(GameEvent::ChatMessage(message)) {
+            let _span = info_span!("chat_input").entered();
+            if let Some(event) = parse_chat_input(&mut chat_input, &world_state.0, message)
+                && let Err(e) = sender.0.send(Traced::new(event))
+            {
                 error!("Error sending chat message: {e:?}");
             }
         } else {
@@ -50,13 +59,82 @@ pub fn handle_chat_input<F, Po>(
         // Handle character input (simplified - in a real implementation you'd need to handle text input events)
         // This is a basic implementation that would need to be enhanced with proper text input handling
         for key in keyboard_input.get_just_pressed() {
-            if let Some(c) = key_to_char(key) {
+            if let Some(c) = key_to_char(key)
+                && chat_input.text.len() < MAX_MESSAGE_LENGTH
+            {
                 chat_input.text.push(c)
             }
         }
     }
 }
 
+/// Parses a chat input line
+///
+/// Handles the `/w name message`, `/join channel` and moderation commands
+/// Other lines go to the current channel
+fn parse_chat_input<W: WorldState, F, Po, J>(
+    chat_input: &mut ChatInputText,
+    world: &W,
+    message: String,
+) -> Option<GameEvent<F, Po, J>> {
+    // Moderation commands stay local
+    let moderation = [
+        ("/mute ", true, true),
+        ("/unmute ", true, false),
+        ("/block ", false, true),
+        ("/unblock ", false, false),
+    ];
+    for (command, mute, enabled) in moderation {
+        if let Some(name) = message.strip_prefix(command) {
+            let name = name.trim();
+            let known = match mute {
+                true => world.mute(name, enabled),
+                false => world.block(name, enabled),
+            };
+            if !known {
+                warn!("Unknown player {name}");
+            }
+            return None;
+        }
+    }
+
+    if let Some(args) = message.strip_prefix("/w ") {
+        let (name, text) = args.trim().split_once(' ')?;
+        return Some(GameEvent::Whisper(
+            name.to_string(),
+            text.trim().to_string(),
+        ));
+    }
+
+    // Bare `/join` goes back to the global channel, `/joined` is plain text
+    let join = match message.as_str() {
+        "/join" => Some(""),
+        message => message.strip_prefix("/join "),
+    };
+    if let Some(args) = join {
+        // Joining the global channel leaves the current one
+        let channel = args.trim();
+        chat_input.channel = match channel {
+            "" | GLOBAL_CHANNEL => None,
+            channel if channel.len() > MAX_CHANNEL_LENGTH => {
+                warn!("Channel name over {MAX_CHANNEL_LENGTH} bytes");
+                return None;
+            }
+            channel => {
+                chat_input.joined.insert(channel.to_string());
+                Some(channel.to_string())
+            }
+        };
+        return None;
+    }
+
+    let text = ChatText::new(message);
+    match &chat_input.channel {
+        Some(channel) => Some(GameEvent::ChannelMessage(channel.clone(), text)),
+        None => Some(GameEvent::ChatMessage(text)),
+    }
+}
+
 /// Helper function to convert KeyCode to character
 fn key_to_char(key: &KeyCode) -> Option<char> {
     match key {
@@ -107,6 +185,9 @@ fn key_to_char(key: &KeyCode) -> Option<char> {
         KeyCode::Numpad8 => Some('8'),
         KeyCode::Numpad9 => Some('9'),
         KeyCode::Space => Some(' '),
+        KeyCode::Slash => Some('/'),
+        KeyCode::Period => Some('.'),
+        KeyCode::Minus => Some('-'),
         _ => None,
     }
 }
@@ -131,8 +212,15 @@ pub fn display_chat_messages<W>(
             recent.push("> Press Enter to type".to_string());
         }
 
-        // Show last 5 messages
-        for m in world_state.0.get_chat_messages().into_iter().rev().take(5) {
+        // Show last 5 messages of the joined channels
+        let messages = world_state.0.get_chat_messages().into_iter().rev();
+        for m in messages
+            .filter(|m| match m.scope() {
+                ChatScope::Channel(name) => chat_input.joined.contains(name),
+                ChatScope::Global | ChatScope::Direct(_) => true,
+            })
+            .take(5)
+        {
             recent.push(m.to_string());
         }
 
@@ -142,7 +230,10 @@ pub fn display_chat_messages<W>(
     // Update chat input field
     if let Ok(mut chat_input_text) = text_queries.p1().single_mut() {
         if chat_input.is_active {
-            chat_input_text.0 = format!("> {}_", chat_input.text);
+            chat_input_text.0 = match &chat_input.channel {
+                Some(channel) => format!("> [#{channel}] {}_", chat_input.text),
+                None => format!("> {}_", chat_input.text),
+            };
         } else {
             chat_input_text.0.clear();
         }
@@ -45,6 +45,10 @@ pub struct ChatBox;
 #[derive(Component)]
 pub struct ChatInput;
 
+/// Component to identify mining zone display
+#[derive(Component)]
+pub struct ZoneBar;
+
 /// Component to identify player count display
 #[derive(Component)]
 pub struct PlayerCount;
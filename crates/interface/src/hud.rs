@@ -40,6 +40,17 @@ pub fn setup_hud(mut commands: Commands) {
         StatusBar,
     );
 
+    // Mining zone display (center)
+    let zone_bar = (
+        Text::default(),
+        TextFont {
+            font_size: HUD_FONT_SIZE,
+            ..default()
+        },
+        TextColor(HUD_TEXT_COLOR),
+        ZoneBar,
+    );
+
     // Player count display (right side)
     let player_bar = (
         Text::default(),
@@ -107,6 +118,7 @@ pub fn setup_hud(mut commands: Commands) {
                 .spawn((top_bar, BackgroundColor(HUD_BG_COLOR), TopHudBar))
                 .with_children(|top_bar| {
                     top_bar.spawn(mine_bar);
+                    top_bar.spawn(zone_bar);
                     top_bar.spawn(player_bar);
                 });
 
@@ -133,3 +145,26 @@ pub fn update_player_count<W>(
         text.0 = format!("Online Players #{player_count}");
     }
 }
+
+/// System to update mining zone display
+pub fn update_zone_status<W>(
+    world_state: Res<WorldStateResource<W>>,
+    mut text_query: Query<&mut Text, With<ZoneBar>>,
+) where
+    W: WorldState + Sync + Send + 'static,
+{
+    let zone = world_state.0.get_zones().into_iter().find(|z| z.local);
+    let status = match zone {
+        Some(z) => format!(
+            "Zone {} | Miners #{} | Hash Share {:.1}%",
+            z.name,
+            z.players,
+            z.share * 100.0
+        ),
+        None => "Outside Mining Zones".to_string(),
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.0.clone_from(&status);
+    }
+}
@@ -2,6 +2,7 @@ mod chat;
 mod components;
 mod hud;
 mod logic;
+mod map;
 mod minings;
 mod movements;
 mod resources;
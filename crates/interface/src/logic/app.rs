@@ -1,3 +1,4 @@
+use crate::map::spawn_map_tiles;
 use crate::minings::{track_mining_events, update_status_bar};
 use crate::movements::{
     capture_key_events, execute_animations, follow_main_player_with_camera,
@@ -5,7 +6,7 @@ use crate::movements::{
 };
 use crate::prelude::*;
 use bevy::prelude::*;
-use game_primitives::events::GameEvent;
+use game_primitives::events::{GameEvent, Traced};
 use game_primitives::{Identifier, Player, Position, WorldState};
 use std::fmt::Display;
 use std::hash::Hash;
@@ -23,10 +24,11 @@ impl Interface {
     /// Runs the Bevy app
     ///
     /// Creates a new Bevy app and runs it
-    pub fn run<W, P, I, F, Po>(channel: Sender<GameEvent<F, Po>>, world: W) -> Self
+    pub fn run<W, P, I, F, Po, J>(channel: Sender<Traced<GameEvent<F, Po, J>>>, world: W) -> Self
     where
         F: Send + Sync + 'static,
         Po: Position<Unit = i32> + Send + Sync + 'static,
+        J: Send + Sync + 'static,
         W: WorldState<Id = I, Player = P> + Sync + Send + 'static,
         P: Identifier<Id = I> + Player + Sync + Send + 'static,
         I: Hash + Eq + Clone + Sync + Send + Display + 'static,
@@ -50,9 +52,10 @@ impl Interface {
             .add_plugins(DefaultPlugins.set(image_plugin).set(asset_plugin))
             // Startup systems
             .add_systems(Startup, setup)
+            .add_systems(Startup, spawn_map_tiles::<W>)
             .add_systems(Startup, setup_hud)
             // Update systems
-            .add_systems(Update, capture_key_events::<F, Po>)
+            .add_systems(Update, capture_key_events::<F, Po, J>)
             .add_systems(Update, check_shutdown_conditions::<W>)
             .add_systems(Update, track_network_movements::<W, P, I>)
             .add_systems(Update, execute_animations::<W, P, I>)
@@ -63,25 +66,19 @@ impl Interface {
             .add_systems(Update, track_mining_events::<W>)
             .add_systems(Update, update_status_bar)
             .add_systems(Update, update_player_count::<W>)
-            .add_systems(Update, handle_chat_input::<F, Po>)
+            .add_systems(Update, update_zone_status::<W>)
+            .add_systems(Update, handle_chat_input::<W, F, Po, J>)
             .add_systems(Update, display_chat_messages::<W>)
+            .add_systems(Update, record_frame_rate::<W>)
             .run();
 
         Self { app }
     }
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(mut commands: Commands) {
     // Spawn 2D camera
     commands.spawn(Camera2d);
-
-    // Spawn the grass background
-    let image = asset_server.load("./assets/textures/background/full.png");
-    commands.spawn((
-        Sprite { image, ..default() },
-        Transform::from_translation(Vec3::new(0., 0., -1.)).with_scale(Vec3::splat(1.5)),
-        Ground,
-    ));
 }
 
 /// System to spawn new player characters in the UI
@@ -175,6 +172,17 @@ fn despawn_quit_players<W, P, I>(
     }
 }
 
+/// System to report the frame rate to the world metrics
+fn record_frame_rate<W: WorldState + Sync + Send + 'static>(
+    time: Res<Time>,
+    world_state: Res<WorldStateResource<W>>,
+) {
+    let delta = time.delta_secs_f64();
+    if delta > 0.0 {
+        world_state.0.record_fps(1.0 / delta);
+    }
+}
+
 /// System to check for external shutdown conditions
 fn check_shutdown_conditions<W: WorldState + Sync + Send + 'static>(
     mut writer: MessageWriter<AppExit>,
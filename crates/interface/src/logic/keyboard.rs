@@ -2,7 +2,7 @@ use crate::prelude::*;
 use bevy::input::keyboard::KeyCode;
 use game_primitives::events::GameEvent;
 
-pub fn keyboard_events<F, P>(key: KeyCode) -> Option<GameEvent<F, P>>
+pub fn keyboard_events<F, P, J>(key: KeyCode) -> Option<GameEvent<F, P, J>>
 where
     P: Position<Unit = i32>,
 {
@@ -0,0 +1,62 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Depth of the bottom map layer, below the players
+const MAP_BASE_DEPTH: f32 = -1.0;
+/// Depth step between map layers
+const MAP_LAYER_DEPTH: f32 = 0.01;
+
+/// System to spawn the tile layers of the world map
+pub fn spawn_map_tiles<W: WorldState + Sync + Send + 'static>(
+    mut commands: Commands,
+    world_state: Res<WorldStateResource<W>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let map = world_state.0.get_map();
+    let (tile_width, tile_height) = map.tile_size();
+
+    // Scale tiles so one tile matches one movement step
+    let scale = Vec3::new(
+        MAGIC_SPEED / tile_width as f32,
+        MAGIC_SPEED / tile_height as f32,
+        1.0,
+    );
+
+    // One image and atlas layout per tileset
+    let mut tilesets = HashMap::new();
+    for tile in map.tiles() {
+        let (image, layout) = tilesets
+            .entry(tile.tileset.clone())
+            .or_insert_with(|| {
+                let image: Handle<Image> = asset_server.load(tile.tileset.clone());
+                let (columns, rows) = tile.grid;
+                let size = UVec2::new(tile_width, tile_height);
+                let layout = TextureAtlasLayout::from_grid(size, columns, rows, None, None);
+
+                (image, texture_atlas_layouts.add(layout))
+            })
+            .clone();
+
+        let texture_atlas = Some(TextureAtlas {
+            layout,
+            index: tile.index as usize,
+        });
+        let translation = Vec3::new(
+            tile.position.0 as f32 * MAGIC_SPEED,
+            tile.position.1 as f32 * MAGIC_SPEED,
+            MAP_BASE_DEPTH + tile.layer as f32 * MAP_LAYER_DEPTH,
+        );
+
+        commands.spawn((
+            Sprite {
+                image,
+                texture_atlas,
+                ..default()
+            },
+            Transform::from_translation(translation).with_scale(scale),
+            Ground,
+        ));
+    }
+}
@@ -2,6 +2,7 @@ use crate::logic::keyboard_events;
 use crate::prelude::*;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
+use game_primitives::events::Traced;
 use std::hash::Hash;
 use std::time::Instant;
 
@@ -33,20 +34,23 @@ pub enum CharacterState {
 }
 
 /// Captures keyboard events and sends them to the core channel
-pub fn capture_key_events<F, Po>(
+pub fn capture_key_events<F, Po, J>(
     mut evr_keys: MessageReader<KeyboardInput>,
-    sender: Res<KeyEventSender<F, Po>>,
+    sender: Res<KeyEventSender<F, Po, J>>,
 ) where
     F: Send + Sync + 'static,
     Po: Position<Unit = i32> + Send + Sync + 'static,
+    J: Send + Sync + 'static,
 {
     for ev in evr_keys.read() {
-        info!("Keyboard event: {ev:?}");
+        trace!(key = ?ev.key_code, "Keyboard event");
 
-        // Send over channel to core
-        if let Some(event) = keyboard_events(ev.key_code)
-            && let Err(e) = sender.0.send(event)
-        {
+        // Send over channel to core, within the span of the key press
+        let Some(event) = keyboard_events(ev.key_code) else {
+            continue;
+        };
+        let _span = info_span!("keyboard_input", key = ?ev.key_code).entered();
+        if let Err(e) = sender.0.send(Traced::new(event)) {
             error!("Error sending keyboard event: {e:?}");
         }
     }
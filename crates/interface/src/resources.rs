@@ -1,13 +1,13 @@
 use crate::movements::PlayerStateInfo;
 use bevy::prelude::*;
-use game_primitives::events::GameEvent;
+use game_primitives::events::{GameEvent, Traced};
 use game_primitives::{Identifier, WorldState};
 use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::Sender;
 
 /// Resource that holds the keyboard event sender
 #[derive(Resource)]
-pub struct KeyEventSender<F, Po>(pub Sender<GameEvent<F, Po>>);
+pub struct KeyEventSender<F, Po, J>(pub Sender<Traced<GameEvent<F, Po, J>>>);
 
 /// Resource that holds the world state
 #[derive(Resource)]
@@ -49,4 +49,8 @@ pub struct MiningRewards {
 pub struct ChatInputText {
     pub text: String,
     pub is_active: bool,
+    /// Channel the messages are sent to, global if none
+    pub channel: Option<String>,
+    /// Channels whose messages are displayed
+    pub joined: HashSet<String>,
 }
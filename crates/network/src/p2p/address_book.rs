@@ -0,0 +1,80 @@
+use crate::prelude::*;
+use libp2p::Multiaddr;
+use libp2p::multiaddr::Protocol;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Maximum number of peers kept in the book
+const MAX_PEERS: usize = 100;
+
+/// Address Book
+///
+/// Known-good peers persisted across restarts
+/// Stored as one `/p2p/` multiaddr per line
+#[derive(Debug, Default)]
+pub struct AddressBook {
+    peers: HashMap<PeerId, Multiaddr>,
+    changed: bool,
+}
+
+impl AddressBook {
+    /// Loads the address book from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut book = Self::default();
+
+        for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let mut address: Multiaddr = line.parse()?;
+            let Some(Protocol::P2p(peer_id)) = address.pop() else {
+                return Err(anyhow!("Missing peer id in {line}"));
+            };
+            book.peers.insert(peer_id, address);
+        }
+
+        Ok(book)
+    }
+
+    /// Saves the address book if it changed
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        if !std::mem::take(&mut self.changed) {
+            return Ok(());
+        }
+
+        let content = self
+            .peers
+            .iter()
+            .map(|(peer_id, address)| address.clone().with(Protocol::P2p(*peer_id)))
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Records a dialable address of a peer
+    pub fn insert(&mut self, peer_id: PeerId, mut address: Multiaddr) {
+        if let Some(Protocol::P2p(_)) = address.iter().last() {
+            address.pop();
+        }
+        if self.peers.len() >= MAX_PEERS && !self.peers.contains_key(&peer_id) {
+            return;
+        }
+
+        if self.peers.insert(peer_id, address.clone()) != Some(address) {
+            self.changed = true;
+        }
+    }
+
+    /// Forgets a peer
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        if self.peers.remove(peer_id).is_some() {
+            self.changed = true;
+        }
+    }
+
+    /// Gets the known peers
+    pub fn peers(&self) -> impl Iterator<Item = (&PeerId, &Multiaddr)> {
+        self.peers.iter()
+    }
+}
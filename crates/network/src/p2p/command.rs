@@ -0,0 +1,58 @@
+use crate::p2p::TopicKind;
+use crate::prelude::*;
+use libp2p::Multiaddr;
+use libp2p::gossipsub::{MessageAcceptance, MessageId};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Reply channel of a command
+pub type Reply<T> = oneshot::Sender<T>;
+
+/// Network Command
+///
+/// Used to control the running network from the application
+#[derive(Debug)]
+pub enum NetworkCommand {
+    /// Sets the application score of a peer for gossipsub scoring
+    Score(PeerId, f64),
+    /// Bans a peer for the given duration and disconnects it
+    Ban(PeerId, Duration),
+    /// Lifts the ban of a peer
+    Unban(PeerId),
+    /// Reports whether a received message should be relayed
+    Validate(MessageId, PeerId, MessageAcceptance),
+    /// Dials an address
+    Dial(Multiaddr, Reply<Result<()>>),
+    /// Closes all connections to a peer
+    Disconnect(PeerId, Reply<Result<()>>),
+    /// Lists the connected peers
+    Peers(Reply<Vec<PeerId>>),
+    /// Subscribes to the topic of a kind
+    Subscribe(TopicKind, Reply<Result<()>>),
+    /// Unsubscribes from the topic of a kind
+    Unsubscribe(TopicKind, Reply<Result<()>>),
+    /// Publishes data on the topic of a kind
+    Publish(TopicKind, Vec<u8>, Reply<Result<()>>),
+    /// Gets the network statistics
+    Stats(Reply<NetworkStats>),
+    /// Closes the connections and stops the network
+    Shutdown(Reply<()>),
+}
+
+/// Network Stats
+///
+/// Snapshot of the running network
+#[derive(Debug, Clone)]
+pub struct NetworkStats {
+    pub peer_id: PeerId,
+    /// Number of connected peers
+    pub peers: usize,
+    /// Number of open connections
+    pub connections: u32,
+    /// Number of banned peers
+    pub banned: usize,
+    /// Subscribed topics
+    pub topics: Vec<TopicKind>,
+    /// Addresses the peer listens on
+    pub listeners: Vec<Multiaddr>,
+}
@@ -0,0 +1,120 @@
+use crate::p2p::TopicKind;
+use crate::prelude::*;
+use libp2p::Multiaddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default listen address for the peer
+const LISTEN_ADDR: &str = "/ip4/0.0.0.0/udp/7331/quic-v1";
+/// Default bootstrap nodes for the peer
+const BOOTSTRAP_NODES: [&str; 1] = ["/ip4/107.22.27.104/udp/7331/quic-v1"];
+/// Default path to the persisted address book
+const PEERS_PATH: &str = "./peers.txt";
+/// Default interval between Kademlia bootstraps
+const BOOTSTRAP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Network Config
+///
+/// Used to configure the addresses and discovery of the peer
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// Address to listen on
+    pub listen: Multiaddr,
+    /// Seed nodes dialed on startup, `/dns4` entries are resolved
+    pub bootstrap: Vec<Multiaddr>,
+    /// File keeping the known-good peers across restarts
+    pub address_book: PathBuf,
+    /// Interval between Kademlia bootstraps and random walks
+    pub bootstrap_interval: Duration,
+    /// Namespace of the topics, separating networks and chains
+    /// Left empty, the world uses its chain id
+    pub namespace: String,
+    /// Topics subscribed on startup
+    pub topics: Vec<TopicKind>,
+    /// Public relays to reserve a circuit on when behind a NAT
+    pub relays: Vec<Multiaddr>,
+    /// Serves circuits to peers behind NATs
+    pub relay_server: bool,
+    /// Answers Kademlia queries even before being confirmed reachable
+    pub kad_server: bool,
+    /// Publicly reachable address announced to peers
+    pub external: Option<Multiaddr>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            listen: LISTEN_ADDR.parse().expect("Valid listen address"),
+            bootstrap: BOOTSTRAP_NODES
+                .iter()
+                .map(|node| node.parse().expect("Valid bootstrap node"))
+                .collect(),
+            address_book: PEERS_PATH.into(),
+            bootstrap_interval: BOOTSTRAP_INTERVAL,
+            namespace: String::new(),
+            topics: TopicKind::ALL.to_vec(),
+            relays: Vec::new(),
+            relay_server: false,
+            kad_server: false,
+            external: None,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Loads the config from the environment
+    ///
+    /// `LISTEN_ADDR`, comma separated `BOOTSTRAP_NODES` and `PEERS_PATH` override the defaults
+    /// `RELAY_NODES`, `RELAY_SERVER`, `KAD_SERVER` and `EXTERNAL_ADDR` configure public nodes
+    /// `NETWORK_NAMESPACE` and comma separated `TOPICS` select the subscribed topics
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+
+        if let Ok(listen) = std::env::var("LISTEN_ADDR") {
+            config.listen = listen.parse()?;
+        }
+        if let Ok(nodes) = std::env::var("BOOTSTRAP_NODES") {
+            config.bootstrap = parse_list(&nodes)?;
+        }
+        if let Ok(path) = std::env::var("PEERS_PATH") {
+            config.address_book = path.into();
+        }
+        if let Ok(nodes) = std::env::var("RELAY_NODES") {
+            config.relays = parse_list(&nodes)?;
+        }
+        if let Ok(relay_server) = std::env::var("RELAY_SERVER") {
+            config.relay_server = matches!(relay_server.as_str(), "1" | "true");
+        }
+        if let Ok(namespace) = std::env::var("NETWORK_NAMESPACE") {
+            config.namespace = namespace;
+        }
+        if let Ok(topics) = std::env::var("TOPICS") {
+            config.topics = topics
+                .split(',')
+                .map(str::trim)
+                .filter(|topic| !topic.is_empty())
+                .map(str::parse)
+                .collect::<Result<_>>()?;
+        }
+        if let Ok(kad_server) = std::env::var("KAD_SERVER") {
+            config.kad_server = matches!(kad_server.as_str(), "1" | "true");
+        }
+        if let Ok(external) = std::env::var("EXTERNAL_ADDR") {
+            config.external = Some(external.parse()?);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parses a comma separated list of multiaddrs
+fn parse_list(list: &str) -> Result<Vec<Multiaddr>> {
+    let addresses = list
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()?;
+
+    Ok(addresses)
+}
@@ -0,0 +1,90 @@
+use crate::p2p::{NetworkCommand, NetworkStats, Reply, TopicKind};
+use crate::prelude::*;
+use libp2p::{Multiaddr, PeerId};
+use tokio::sync::{mpsc, oneshot};
+
+/// Network Handle
+///
+/// Used to control the network once started
+/// Requests are answered by the swarm loop
+#[derive(Debug, Clone)]
+pub struct NetworkHandle {
+    commands: mpsc::Sender<NetworkCommand>,
+    peer_id: PeerId,
+}
+
+impl NetworkHandle {
+    /// Creates a new handle over the command channel of a peer
+    pub fn new(commands: mpsc::Sender<NetworkCommand>, peer_id: PeerId) -> Self {
+        Self { commands, peer_id }
+    }
+
+    /// Gets the id of the local peer
+    pub fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    /// Sends a command without waiting for its outcome
+    pub async fn send(&self, command: NetworkCommand) -> Result<()> {
+        self.commands
+            .send(command)
+            .await
+            .map_err(|_| anyhow!("Network is not running"))
+    }
+
+    /// Sends a command and waits for its reply
+    async fn request<T>(&self, command: impl FnOnce(Reply<T>) -> NetworkCommand) -> Result<T> {
+        let (reply, response) = oneshot::channel();
+        self.send(command(reply)).await?;
+
+        response
+            .await
+            .map_err(|_| anyhow!("Network dropped the request"))
+    }
+
+    /// Dials an address
+    pub async fn dial(&self, address: Multiaddr) -> Result<()> {
+        self.request(|reply| NetworkCommand::Dial(address, reply))
+            .await?
+    }
+
+    /// Closes all connections to a peer
+    pub async fn disconnect(&self, peer_id: PeerId) -> Result<()> {
+        self.request(|reply| NetworkCommand::Disconnect(peer_id, reply))
+            .await?
+    }
+
+    /// Lists the connected peers
+    pub async fn peers(&self) -> Result<Vec<PeerId>> {
+        self.request(NetworkCommand::Peers).await
+    }
+
+    /// Subscribes to the topic of a kind
+    pub async fn subscribe(&self, kind: TopicKind) -> Result<()> {
+        self.request(|reply| NetworkCommand::Subscribe(kind, reply))
+            .await?
+    }
+
+    /// Unsubscribes from the topic of a kind
+    pub async fn unsubscribe(&self, kind: TopicKind) -> Result<()> {
+        self.request(|reply| NetworkCommand::Unsubscribe(kind, reply))
+            .await?
+    }
+
+    /// Publishes data on the topic of a kind
+    pub async fn publish_to(&self, kind: TopicKind, data: impl Into<Vec<u8>>) -> Result<()> {
+        let data = data.into();
+        self.request(|reply| NetworkCommand::Publish(kind, data, reply))
+            .await?
+    }
+
+    /// Gets the network statistics
+    pub async fn stats(&self) -> Result<NetworkStats> {
+        self.request(NetworkCommand::Stats).await
+    }
+
+    /// Closes the connections and stops the network
+    pub async fn shutdown(&self) -> Result<()> {
+        self.request(NetworkCommand::Shutdown).await
+    }
+}
@@ -0,0 +1,29 @@
+use crate::p2p::TopicKind;
+use crate::prelude::*;
+use libp2p::gossipsub::{Message, MessageId};
+use tracing::Span;
+
+/// Received Message
+///
+/// Gossipsub message held back until the application validates it
+#[derive(Debug, Clone)]
+pub struct ReceivedMessage {
+    /// Identifier used to report the validation result
+    pub id: MessageId,
+    /// Peer that forwarded the message
+    pub propagation_source: PeerId,
+    /// Kind of the topic the message was received on
+    pub kind: Option<TopicKind>,
+    pub message: Message,
+}
+
+/// Traceable
+///
+/// Used to publish a message within the span it was sent from
+/// The network runs in its own task, out of the sender's span
+pub trait Traceable {
+    /// Span the message is published within
+    fn span(&self) -> Span {
+        Span::current()
+    }
+}
@@ -1,4 +1,256 @@
+mod address_book;
+mod command;
+mod config;
+mod handle;
+mod message;
 mod network;
+mod topic;
 
 // Crate Public API
+pub use address_book::AddressBook;
+pub use command::{NetworkCommand, NetworkStats, Reply};
+pub use config::NetworkConfig;
+pub use handle::NetworkHandle;
+pub use message::{ReceivedMessage, Traceable};
 pub use network::{GAME_PROTO_NAME, Network, Peer2Peer};
+pub use topic::{GameTopic, TopicKind, Topical};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use libp2p::core::muxing::StreamMuxerBox;
+    use libp2p::core::transport::{Boxed, MemoryTransport};
+    use libp2p::core::{Transport, upgrade};
+    use libp2p::futures::StreamExt;
+    use libp2p::multiaddr::Protocol;
+    use libp2p::swarm::SwarmEvent;
+    use libp2p::{Swarm, SwarmBuilder, relay};
+    use std::time::Duration;
+
+    #[test]
+    fn test_address_book_roundtrip() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("peers-{}.txt", PeerId::random()));
+        let peer_id = PeerId::random();
+        let address = format!("/dns4/seed.example.com/udp/7331/quic-v1/p2p/{peer_id}");
+
+        // Peer ids are split from the stored address
+        let mut book = AddressBook::default();
+        book.insert(peer_id, address.parse()?);
+        book.save(&path)?;
+
+        let loaded = AddressBook::load(&path)?;
+        let peers = loaded.peers().collect::<Vec<_>>();
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].0, &peer_id);
+        assert_eq!(
+            peers[0].1.to_string(),
+            "/dns4/seed.example.com/udp/7331/quic-v1"
+        );
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct Presence;
+
+    impl From<Presence> for Vec<u8> {
+        fn from(_: Presence) -> Self {
+            Vec::new()
+        }
+    }
+
+    impl Topical for Presence {
+        fn topic_kind(&self) -> TopicKind {
+            TopicKind::Presence
+        }
+    }
+
+    impl Traceable for Presence {}
+
+    #[test]
+    fn test_namespaced_topics() -> Result<()> {
+        let topic = GameTopic::new("8453", "shares".parse()?);
+        assert_eq!(topic.to_string(), "/fonketh/8453/shares");
+
+        // Same category on another chain is another topic
+        let other = GameTopic::new("1", TopicKind::Shares);
+        assert_ne!(topic.hash(), other.hash());
+        assert!("blocks".parse::<TopicKind>().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_relay_server_node() -> Result<()> {
+        // Reservations advertise the external address, the listen one here
+        let address: Multiaddr = "/ip4/127.0.0.1/udp/47331/quic-v1".parse()?;
+        let config = NetworkConfig {
+            listen: address.clone(),
+            bootstrap: Vec::new(),
+            address_book: std::env::temp_dir().join("relay-peers.txt"),
+            relay_server: true,
+            external: Some(address.clone()),
+            ..Default::default()
+        };
+
+        // Relay servers run alongside the relay client and hole punching
+        let keypair = Keypair::ed25519_from_bytes([7u8; 32])?;
+        let relay_id = keypair.public().to_peer_id();
+        let network = Peer2Peer::<Presence>::build(keypair, config)?;
+        let (_sender, _listener) = network.start();
+
+        // A private node reserves a circuit on it
+        let mut client = SwarmBuilder::with_new_identity()
+            .with_tokio()
+            .with_quic()
+            .with_relay_client(noise::Config::new, yamux::Config::default)?
+            .with_behaviour(|_, relay_client| relay_client)?
+            .build();
+        let circuit = address
+            .with(Protocol::P2p(relay_id))
+            .with(Protocol::P2pCircuit);
+        client.listen_on(circuit.clone())?;
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                match client.select_next_some().await {
+                    SwarmEvent::Behaviour(relay::client::Event::ReservationReqAccepted {
+                        relay_peer_id,
+                        ..
+                    }) => break assert_eq!(relay_peer_id, relay_id),
+                    // The relay may not listen yet
+                    SwarmEvent::ListenerClosed { .. } => {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        client.listen_on(circuit.clone())?;
+                    }
+                    _ => {}
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+        Ok(())
+    }
+
+    /// Builds an authenticated transport over memory channels
+    fn memory_transport(
+        key: &Keypair,
+    ) -> Result<Boxed<(PeerId, StreamMuxerBox)>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(MemoryTransport::default()
+            .upgrade(upgrade::Version::V1)
+            .authenticate(noise::Config::new(key)?)
+            .multiplex(yamux::Config::default())
+            .boxed())
+    }
+
+    /// Builds a relay client reachable over memory channels
+    fn relay_client() -> Result<Swarm<relay::client::Behaviour>> {
+        Ok(SwarmBuilder::with_new_identity()
+            .with_tokio()
+            .with_other_transport(memory_transport)?
+            .with_relay_client(noise::Config::new, yamux::Config::default)?
+            .with_behaviour(|_, relay_client| relay_client)?
+            .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(30)))
+            .build())
+    }
+
+    #[tokio::test]
+    async fn test_relay_circuit() -> Result<()> {
+        let mut relay = SwarmBuilder::with_new_identity()
+            .with_tokio()
+            .with_other_transport(memory_transport)?
+            .with_behaviour(|key| {
+                relay::Behaviour::new(key.public().to_peer_id(), relay::Config::default())
+            })?
+            .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(30)))
+            .build();
+        let relay_id = *relay.local_peer_id();
+
+        // Reservations advertise the external addresses of the relay
+        relay.listen_on(Protocol::Memory(0).into())?;
+        let relay_address = loop {
+            if let SwarmEvent::NewListenAddr { address, .. } = relay.select_next_some().await {
+                break address;
+            }
+        };
+        relay.add_external_address(relay_address.clone());
+        tokio::spawn(async move {
+            loop {
+                relay.select_next_some().await;
+            }
+        });
+
+        let circuit = relay_address
+            .with(Protocol::P2p(relay_id))
+            .with(Protocol::P2pCircuit);
+        let timeout = Duration::from_secs(10);
+
+        // A private node listens through the relay
+        let mut listener = relay_client()?;
+        let listener_id = *listener.local_peer_id();
+        listener.listen_on(circuit.clone())?;
+        tokio::time::timeout(timeout, async {
+            loop {
+                if let SwarmEvent::Behaviour(relay::client::Event::ReservationReqAccepted {
+                    relay_peer_id,
+                    ..
+                }) = listener.select_next_some().await
+                {
+                    assert_eq!(relay_peer_id, relay_id);
+                    break;
+                }
+            }
+        })
+        .await?;
+        tokio::spawn(async move {
+            loop {
+                listener.select_next_some().await;
+            }
+        });
+
+        // Another private node reaches it through the circuit
+        let mut dialer = relay_client()?;
+        dialer.dial(circuit.with(Protocol::P2p(listener_id)))?;
+        let endpoint = tokio::time::timeout(timeout, async {
+            loop {
+                match dialer.select_next_some().await {
+                    SwarmEvent::ConnectionEstablished {
+                        peer_id, endpoint, ..
+                    } if peer_id == listener_id => break Ok(endpoint),
+                    SwarmEvent::OutgoingConnectionError { error, .. } => break Err(error),
+                    _ => {}
+                }
+            }
+        })
+        .await??;
+        assert!(endpoint.is_relayed());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_network_handle() -> Result<()> {
+        let config = NetworkConfig {
+            listen: "/ip4/127.0.0.1/udp/0/quic-v1".parse()?,
+            bootstrap: Vec::new(),
+            address_book: std::env::temp_dir().join("handle-peers.txt"),
+            ..Default::default()
+        };
+
+        let keypair = Keypair::ed25519_from_bytes([9u8; 32])?;
+        let network = Peer2Peer::<Presence>::build(keypair, config)?;
+        let handle = network.handle();
+        let (_sender, _listener) = network.start();
+
+        // Requests are answered by the running swarm
+        assert!(handle.peers().await?.is_empty());
+        handle.unsubscribe(TopicKind::Chat).await?;
+        let stats = handle.stats().await?;
+        assert!(!stats.topics.contains(&TopicKind::Chat));
+        assert!(stats.topics.contains(&TopicKind::Shares));
+
+        // Nothing answers once shut down
+        handle.shutdown().await?;
+        assert!(handle.stats().await.is_err());
+        Ok(())
+    }
+}
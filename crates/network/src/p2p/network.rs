@@ -1,27 +1,48 @@
+use crate::p2p::{
+    AddressBook, GameTopic, NetworkCommand, NetworkConfig, NetworkHandle, NetworkStats,
+    ReceivedMessage, Reply, TopicKind, Topical, Traceable,
+};
 use crate::prelude::*;
+use libp2p::allow_block_list::{self, BlockedPeers};
 use libp2p::futures::StreamExt;
-use libp2p::gossipsub::{IdentTopic, Message};
+use libp2p::gossipsub::TopicHash;
 use libp2p::identity::Keypair;
+use libp2p::metrics::{Metrics, Recorder};
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::dial_opts::DialOpts;
 use libp2p::swarm::{NetworkBehaviour, SwarmEvent};
-use libp2p::{StreamProtocol, Swarm};
+use libp2p::{Multiaddr, StreamProtocol, Swarm, autonat, dcutr, identify, relay};
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::Registry;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 use std::{fmt::Debug, time::Duration};
-use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::sync::mpsc::{
+    self,
+    error::{TryRecvError, TrySendError},
+};
 
 /// Game protocol name
 pub const GAME_PROTO_NAME: StreamProtocol = StreamProtocol::new("/game/kad/1.0.0");
-/// Listen address for the peer
-const LISTEN_ADDR: &str = "/ip4/0.0.0.0/udp/7331/quic-v1";
-/// Bootstrap nodes for the peer
-const BOOTSTRAP_NODES: [&str; 1] = ["/ip4/107.22.27.104/udp/7331/quic-v1"];
-/// Topics to subscribe to
-const TOPICS: [&str; 1] = ["game_events"];
+/// Time given to pending gossip before closing connections
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+/// Identify protocol version
+const IDENTIFY_PROTO_VERSION: &str = "/game/id/1.0.0";
 
 #[derive(NetworkBehaviour)]
 pub struct MyBehaviour {
     gossipsub: gossipsub::Behaviour,
     mdns: mdns::tokio::Behaviour,
     kad: kad::Behaviour<kad::store::MemoryStore>,
+    blocked: allow_block_list::Behaviour<BlockedPeers>,
+    identify: identify::Behaviour,
+    autonat: autonat::Behaviour,
+    relay_client: relay::client::Behaviour,
+    relay: Toggle<relay::Behaviour>,
+    dcutr: dcutr::Behaviour,
 }
 
 pub trait GossipTypes {
@@ -32,23 +53,40 @@ pub trait GossipTypes {
 pub trait Network: GossipTypes {
     fn listen(&mut self) -> Result<()>;
     fn subscribe(&mut self, topic: Self::Topic) -> Result<()>;
+    fn unsubscribe(&mut self, topic: Self::Topic) -> Result<()>;
     fn send(&mut self, topic: Self::Topic, data: Self::Data) -> Result<()>;
 }
 
 pub struct Peer2Peer<M> {
     pub sender: mpsc::Sender<M>,
     receiver: mpsc::Receiver<M>,
-    pub listener: Option<mpsc::Receiver<Message>>,
-    talker: mpsc::Sender<Message>,
+    pub listener: Option<mpsc::Receiver<ReceivedMessage>>,
+    talker: mpsc::Sender<ReceivedMessage>,
+    controller: mpsc::Sender<NetworkCommand>,
+    commands: mpsc::Receiver<NetworkCommand>,
+    bans: HashMap<PeerId, Instant>,
+    topics: HashMap<TopicHash, TopicKind>,
+    closing: Option<(Reply<()>, Instant)>,
+    config: NetworkConfig,
+    book: AddressBook,
+    registry: Arc<Registry>,
+    metrics: Metrics,
+    peers: Gauge,
     swarm: Swarm<MyBehaviour>,
 }
 
 impl<M> Peer2Peer<M>
 where
-    M: Into<Vec<u8>> + Send + Sync + 'static + Debug,
+    M: Into<Vec<u8>> + Topical + Traceable + Send + Sync + 'static + Debug,
 {
-    pub fn build(keypair: Keypair) -> Result<Self> {
-        let swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
+    pub fn build(keypair: Keypair, config: NetworkConfig) -> Result<Self> {
+        let relay_server = config.relay_server;
+        let label = (
+            Cow::Borrowed("network"),
+            Cow::Owned(config.namespace.clone()),
+        );
+        let mut registry = Registry::with_labels(std::iter::once(label));
+        let mut swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
             .with_tokio()
             .with_tcp(
                 tcp::Config::default(),
@@ -56,36 +94,83 @@ where
                 yamux::Config::default,
             )?
             .with_quic()
+            .with_dns()?
+            .with_relay_client(noise::Config::new, yamux::Config::default)?
+            .with_bandwidth_metrics(&mut registry)
             // Create behavior.
-            .with_behaviour(|key| {
+            .with_behaviour(|key, relay_client| {
                 // Set a custom gossipsub configuration
-                let gossipsub_config = gossipsub::ConfigBuilder::default().build()?;
+                // Messages are only relayed once validated by the application
+                let gossipsub_config = gossipsub::ConfigBuilder::default()
+                    .validation_mode(gossipsub::ValidationMode::Strict)
+                    .validate_messages()
+                    .build()?;
 
                 // build a gossipsub network behaviour
-                let gossipsub = gossipsub::Behaviour::new(
+                let mut gossipsub = gossipsub::Behaviour::new(
                     gossipsub::MessageAuthenticity::Signed(key.clone()),
                     gossipsub_config,
                 )?;
 
+                // Enable peer scoring, fed by the application scores
+                gossipsub.with_peer_score(
+                    gossipsub::PeerScoreParams::default(),
+                    gossipsub::PeerScoreThresholds::default(),
+                )?;
+
                 let mdns = mdns::tokio::Behaviour::new(
                     mdns::Config::default(),
                     key.public().to_peer_id(),
                 )?;
 
+                let peer_id = key.public().to_peer_id();
                 let cfg = kad::Config::new(GAME_PROTO_NAME);
-                let store = kad::store::MemoryStore::new(key.public().to_peer_id());
-                let kad = kad::Behaviour::with_config(key.public().to_peer_id(), store, cfg);
+                let store = kad::store::MemoryStore::new(peer_id);
+                let kad = kad::Behaviour::with_config(peer_id, store, cfg);
+
+                // NAT traversal, learns reachability and upgrades relayed connections
+                let identify = identify::Behaviour::new(identify::Config::new(
+                    IDENTIFY_PROTO_VERSION.to_string(),
+                    key.public(),
+                ));
+                let autonat = autonat::Behaviour::new(peer_id, autonat::Config::default());
+                let relay =
+                    relay_server.then(|| relay::Behaviour::new(peer_id, relay::Config::default()));
+                let dcutr = dcutr::Behaviour::new(peer_id);
 
                 Ok(MyBehaviour {
                     gossipsub,
                     mdns,
                     kad,
+                    blocked: Default::default(),
+                    identify,
+                    autonat,
+                    relay_client,
+                    relay: relay.into(),
+                    dcutr,
                 })
             })?
             .build();
 
+        // Public nodes answer queries right away
+        if config.kad_server {
+            swarm.behaviour_mut().kad.set_mode(Some(kad::Mode::Server));
+        }
+
+        // Swarm and peer metrics
+        let metrics = Metrics::new(&mut registry);
+        let peers = Gauge::default();
+        registry.register("peers", "Number of connected peers", peers.clone());
+
         let (sender, receiver) = mpsc::channel(100);
         let (talker, listener) = mpsc::channel(100);
+        let (controller, commands) = mpsc::channel(100);
+
+        // Restore the known peers
+        let book = AddressBook::load(&config.address_book).unwrap_or_else(|e| {
+            debug!("No address book loaded: {e}");
+            AddressBook::default()
+        });
 
         Ok(Self {
             swarm,
@@ -93,74 +178,395 @@ where
             receiver,
             listener: Some(listener),
             talker,
+            controller,
+            commands,
+            bans: Default::default(),
+            topics: Default::default(),
+            closing: None,
+            config,
+            book,
+            registry: Arc::new(registry),
+            metrics,
+            peers,
         })
     }
 
+    /// Gets a handle to control the network once started
+    pub fn handle(&self) -> NetworkHandle {
+        NetworkHandle::new(self.controller.clone(), *self.swarm.local_peer_id())
+    }
+
+    /// Gets the metrics registry of the network
+    pub fn metrics(&self) -> Arc<Registry> {
+        self.registry.clone()
+    }
+
+    /// Gets the namespaced topic of a kind
+    fn topic(&self, kind: TopicKind) -> GameTopic {
+        GameTopic::new(self.config.namespace.clone(), kind)
+    }
+
+    /// Applies a command to the swarm
+    fn handle_command(&mut self, command: NetworkCommand) {
+        let behaviour = self.swarm.behaviour_mut();
+
+        match command {
+            NetworkCommand::Score(peer_id, score) => {
+                behaviour.gossipsub.set_application_score(&peer_id, score);
+            }
+            NetworkCommand::Ban(peer_id, duration) => {
+                warn!(%peer_id, duration = duration.as_secs(), "Banning peer");
+                behaviour.gossipsub.blacklist_peer(&peer_id);
+                behaviour.blocked.block_peer(peer_id);
+                // Bans past the end of the clock last until the peer is unbanned
+                match Instant::now().checked_add(duration) {
+                    Some(until) => self.bans.insert(peer_id, until),
+                    None => self.bans.remove(&peer_id),
+                };
+            }
+            NetworkCommand::Unban(peer_id) => {
+                info!(%peer_id, "Unbanning peer");
+                behaviour.gossipsub.remove_blacklisted_peer(&peer_id);
+                behaviour.blocked.unblock_peer(peer_id);
+                self.bans.remove(&peer_id);
+            }
+            NetworkCommand::Validate(message_id, source, acceptance) => {
+                trace!(%message_id, ?acceptance, "Message validated");
+                if !behaviour.gossipsub.report_message_validation_result(
+                    &message_id,
+                    &source,
+                    acceptance,
+                ) {
+                    debug!("Validated message {message_id} is no longer cached");
+                }
+            }
+            NetworkCommand::Dial(address, reply) => {
+                let result = self.swarm.dial(address).map_err(Into::into);
+                let _ = reply.send(result);
+            }
+            NetworkCommand::Disconnect(peer_id, reply) => {
+                let result = self
+                    .swarm
+                    .disconnect_peer_id(peer_id)
+                    .map_err(|_| anyhow!("Peer {peer_id} is not connected"));
+                let _ = reply.send(result);
+            }
+            NetworkCommand::Peers(reply) => {
+                let _ = reply.send(self.swarm.connected_peers().copied().collect());
+            }
+            NetworkCommand::Subscribe(kind, reply) => {
+                let _ = reply.send(self.subscribe(self.topic(kind)));
+            }
+            NetworkCommand::Unsubscribe(kind, reply) => {
+                let _ = reply.send(self.unsubscribe(self.topic(kind)));
+            }
+            NetworkCommand::Publish(kind, data, reply) => {
+                let topic = self.topic(kind).ident();
+                let result = self
+                    .swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .publish(topic, data)
+                    .map(|_| ())
+                    .map_err(Into::into);
+                let _ = reply.send(result);
+            }
+            NetworkCommand::Stats(reply) => {
+                let _ = reply.send(self.stats());
+            }
+            NetworkCommand::Shutdown(reply) => {
+                // Keep polling the swarm for a while to flush pending gossip
+                info!("Flushing pending gossip before shutdown");
+                if let Err(e) = self.publish_pending() {
+                    error!("Failed to flush pending gossip: {e}");
+                }
+                self.closing = Some((reply, Instant::now() + FLUSH_TIMEOUT));
+            }
+        }
+    }
+
+    /// Publishes the data queued by the application
+    fn publish_pending(&mut self) -> Result<()> {
+        loop {
+            match self.receiver.try_recv() {
+                Err(TryRecvError::Empty) => return Ok(()),
+                Ok(data) => {
+                    let topic = self.topic(data.topic_kind());
+                    if let Err(e) = self.send(topic, data) {
+                        error!("Publish error: {e:?}");
+                    }
+                }
+                Err(e) => {
+                    error!("Receive error: {e:?}");
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+
+    /// Gets the network statistics
+    fn stats(&self) -> NetworkStats {
+        let info = self.swarm.network_info();
+
+        NetworkStats {
+            peer_id: *self.swarm.local_peer_id(),
+            peers: info.num_peers(),
+            connections: info.connection_counters().num_connections(),
+            banned: self.bans.len(),
+            topics: self.topics.values().copied().collect(),
+            listeners: self.swarm.listeners().cloned().collect(),
+        }
+    }
+
+    /// Leaves the topics and closes all connections
+    fn close(&mut self) {
+        info!("Shutting down network");
+        for kind in self.topics.values().copied().collect::<Vec<_>>() {
+            let _ = self.unsubscribe(self.topic(kind));
+        }
+        for peer_id in self.swarm.connected_peers().copied().collect::<Vec<_>>() {
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+
+        if let Err(e) = self.book.save(&self.config.address_book) {
+            error!("Failed to save address book: {e}");
+        }
+    }
+
+    /// Lifts the bans that have expired
+    fn expire_bans(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .bans
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+
+        for peer_id in expired {
+            self.handle_command(NetworkCommand::Unban(peer_id));
+        }
+    }
+
+    /// Dials the bootstrap nodes and the known peers
+    fn dial_known_peers(&mut self) {
+        for node in self.config.bootstrap.clone() {
+            let opts = DialOpts::unknown_peer_id().address(node.clone()).build();
+            if let Err(e) = self.swarm.dial(opts) {
+                warn!("Failed to dial bootstrap node {node}: {e}");
+            }
+        }
+
+        let peers = self
+            .book
+            .peers()
+            .map(|(peer_id, address)| (*peer_id, address.clone()))
+            .collect::<Vec<_>>();
+        for (peer_id, address) in peers {
+            let behaviour = self.swarm.behaviour_mut();
+            behaviour.kad.add_address(&peer_id, address.clone());
+
+            let opts = DialOpts::peer_id(peer_id).addresses(vec![address]).build();
+            if let Err(e) = self.swarm.dial(opts) {
+                debug!("Failed to dial known peer {peer_id}: {e}");
+            }
+        }
+    }
+
+    /// Refreshes the routing table
+    ///
+    /// Bootstraps Kademlia and walks towards a random peer id
+    fn bootstrap(&mut self) {
+        if let Err(e) = self.book.save(&self.config.address_book) {
+            error!("Failed to save address book: {e}");
+        }
+
+        let kad = &mut self.swarm.behaviour_mut().kad;
+        if let Err(e) = kad.bootstrap() {
+            debug!("Kademlia bootstrap skipped: {e}");
+            return;
+        }
+        kad.get_closest_peers(PeerId::random());
+    }
+
+    /// Listens through the configured relays
+    ///
+    /// Used once AutoNAT finds the peer is not publicly reachable
+    fn reserve_relays(&mut self) {
+        for relay in self.config.relays.clone() {
+            let address = relay.with(Protocol::P2pCircuit);
+            match self.swarm.listen_on(address.clone()) {
+                Ok(_) => info!("Reserving relay circuit on {address}"),
+                Err(e) => warn!("Failed to listen on relay {address}: {e}"),
+            }
+        }
+    }
+
+    /// Records the address of a reachable peer
+    fn add_known_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.swarm
+            .behaviour_mut()
+            .kad
+            .add_address(&peer_id, address.clone());
+        self.book.insert(peer_id, address);
+    }
+
     async fn run(mut self) -> Result<()> {
         info!("Running network PeerID: {}", self.swarm.local_peer_id());
         self.listen()?;
-
-        // Connect to the bootstrap nodes
-        for node in BOOTSTRAP_NODES {
-            let opts = DialOpts::unknown_peer_id().address(node.parse()?).build();
-            self.swarm.dial(opts)?;
+        if let Some(external) = self.config.external.clone() {
+            self.swarm.add_external_address(external);
         }
 
-        for topic in TOPICS {
-            self.subscribe(topic)?;
+        // Connect to the bootstrap nodes and known peers
+        self.dial_known_peers();
+
+        for kind in self.config.topics.clone() {
+            self.subscribe(self.topic(kind))?;
         }
 
         // Kick it off
         let mut interval = tokio::time::interval(Duration::from_millis(10));
+        let mut bans_interval = tokio::time::interval(Duration::from_secs(10));
+        let mut bootstrap_interval = tokio::time::interval(self.config.bootstrap_interval);
         loop {
+            let closing_at = self
+                .closing
+                .as_ref()
+                .map(|(_, deadline)| tokio::time::Instant::from_std(*deadline));
+
             tokio::select! {
-                _ = interval.tick() => loop {
-                    match self.receiver.try_recv() {
-                        Err(TryRecvError::Empty) => break,
-                        Ok(data) => {
-                            if let Err(e) = self.send(TOPICS[0], data) {
-                                error!("Publish error: {e:?}");
-                            }
-                        },
-                        Err(e) => {
-                            error!("Receive error: {e:?}");
-                            return Err(e.into());
-                        },
+                _ = tokio::time::sleep_until(closing_at.unwrap_or_else(tokio::time::Instant::now)), if closing_at.is_some() => {
+                    self.close();
+                    if let Some((reply, _)) = self.closing.take() {
+                        let _ = reply.send(());
                     }
+                    return Ok(());
                 },
-                SwarmEvent::Behaviour(event) = self.swarm.select_next_some() => match event {
-                    MyBehaviourEvent::Mdns(mdns::Event::Discovered(list)) => {
-                        for (peer_id, multiaddr) in list {
-                            info!("mDNS discovered a new peer: {peer_id}");
-                            let behaviour = self.swarm.behaviour_mut();
-                            behaviour.kad.add_address(&peer_id, multiaddr);
-                            behaviour.gossipsub.add_explicit_peer(&peer_id);
-                            info!("Added explicit peer {peer_id}");
+                _ = bans_interval.tick() => self.expire_bans(),
+                _ = bootstrap_interval.tick() => self.bootstrap(),
+                Some(command) = self.commands.recv() => self.handle_command(command),
+                _ = interval.tick() => self.publish_pending()?,
+                event = self.swarm.select_next_some() => {
+                    self.metrics.record(&event);
+                    match event {
+                        SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
+                            self.peers.set(self.swarm.connected_peers().count() as i64);
+
+                            // Only dialed addresses are known to be reachable
+                            if endpoint.is_dialer() {
+                                self.add_known_peer(peer_id, endpoint.get_remote_address().clone());
+                            }
                         }
-                    }
-                    MyBehaviourEvent::Mdns(mdns::Event::Expired(list)) => {
-                        for (peer_id, multiaddr) in list {
-                            info!("mDNS discover peer has expired: {peer_id}");
-                            let behaviour = self.swarm.behaviour_mut();
-                            behaviour.kad.remove_address(&peer_id, &multiaddr);
-                            behaviour.gossipsub.remove_explicit_peer(&peer_id);
-                            info!("Removed explicit peer {peer_id}");
+                        SwarmEvent::ConnectionClosed { .. } => {
+                            self.peers.set(self.swarm.connected_peers().count() as i64);
                         }
+                        SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), error, .. } => {
+                            debug!("Failed to connect to {peer_id}: {error}");
+                            self.book.remove(&peer_id);
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Mdns(mdns::Event::Discovered(list))) => {
+                            for (peer_id, multiaddr) in list {
+                                info!("mDNS discovered a new peer: {peer_id}");
+                                let behaviour = self.swarm.behaviour_mut();
+                                behaviour.kad.add_address(&peer_id, multiaddr);
+                                behaviour.gossipsub.add_explicit_peer(&peer_id);
+                                info!("Added explicit peer {peer_id}");
+                            }
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Mdns(mdns::Event::Expired(list))) => {
+                            for (peer_id, multiaddr) in list {
+                                info!("mDNS discover peer has expired: {peer_id}");
+                                let behaviour = self.swarm.behaviour_mut();
+                                behaviour.kad.remove_address(&peer_id, &multiaddr);
+                                behaviour.gossipsub.remove_explicit_peer(&peer_id);
+                                info!("Removed explicit peer {peer_id}");
+                            }
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Kad(kad::Event::RoutingUpdated {
+                            peer,
+                            addresses,
+                            ..
+                        })) => {
+                            debug!("Kademlia routing updated with {peer}");
+                            self.book.insert(peer, addresses.first().clone());
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Identify(identify::Event::Received {
+                            peer_id,
+                            info,
+                            ..
+                        })) => {
+                            let kad = &mut self.swarm.behaviour_mut().kad;
+                            for address in info.listen_addrs {
+                                kad.add_address(&peer_id, address);
+                            }
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Autonat(autonat::Event::StatusChanged {
+                            old,
+                            new,
+                        })) => {
+                            info!("NAT status changed from {old:?} to {new:?}");
+                            if matches!(new, autonat::NatStatus::Private) {
+                                self.reserve_relays();
+                            }
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::RelayClient(
+                            relay::client::Event::ReservationReqAccepted { relay_peer_id, .. },
+                        )) => {
+                            info!("Relay reservation accepted by {relay_peer_id}");
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Relay(event)) => {
+                            debug!("Relay server event: {event:?}");
+                        }
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Dcutr(dcutr::Event {
+                            remote_peer_id,
+                            result,
+                        })) => match result {
+                            Ok(_) => info!("Hole punched a direct connection to {remote_peer_id}"),
+                            Err(e) => debug!("Hole punching to {remote_peer_id} failed: {e}"),
+                        },
+                        SwarmEvent::Behaviour(MyBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                            propagation_source,
+                            message_id,
+                            message,
+                        })) => {
+                            trace!(
+                                peer_id = %propagation_source,
+                                %message_id,
+                                topic = %message.topic,
+                                bytes = message.data.len(),
+                                "Gossipsub message received"
+                            );
+                            let received = ReceivedMessage {
+                                kind: self.topics.get(&message.topic).copied(),
+                                id: message_id,
+                                propagation_source,
+                                message,
+                            };
+                            // Never wait on the application, it may be waiting on the commands
+                            if let Err(e) = self.talker.try_send(received) {
+                                let received = match e {
+                                    TrySendError::Full(received) => {
+                                        warn!("Inbound queue full, dropping gossipsub message");
+                                        received
+                                    }
+                                    TrySendError::Closed(received) => received,
+                                };
+                                self.swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                    &received.id,
+                                    &received.propagation_source,
+                                    gossipsub::MessageAcceptance::Ignore,
+                                );
+                            }
+                        }
+                        _ => {}
                     }
-                    MyBehaviourEvent::Gossipsub(gossipsub::Event::Message {
-                        message,
-                        ..
-                    }) => {
-                        info!("Gossipsub message received: {message:?}",);
-                        self.talker.send(message).await.unwrap();
-                    }
-                    _ => {}
                 }
             }
         }
     }
 
-    pub fn start(mut self) -> (mpsc::Sender<M>, mpsc::Receiver<Message>) {
+    pub fn start(mut self) -> (mpsc::Sender<M>, mpsc::Receiver<ReceivedMessage>) {
         let sender = self.sender.clone();
         let listener = self.listener.take().unwrap();
         tokio::spawn(self.run());
@@ -169,32 +575,49 @@ where
 }
 
 impl<M> GossipTypes for Peer2Peer<M> {
-    type Topic = &'static str;
+    type Topic = GameTopic;
     type Data = M;
 }
 
 impl<M> Network for Peer2Peer<M>
 where
-    M: Into<Vec<u8>> + Send + Sync + 'static,
+    M: Into<Vec<u8>> + Traceable + Send + Sync + 'static,
 {
     fn listen(&mut self) -> Result<()> {
-        self.swarm.listen_on(LISTEN_ADDR.parse()?)?;
+        self.swarm.listen_on(self.config.listen.clone())?;
 
         Ok(())
     }
 
     fn subscribe(&mut self, topic: Self::Topic) -> Result<()> {
-        let topic = IdentTopic::new(topic);
-        self.swarm.behaviour_mut().gossipsub.subscribe(&topic)?;
+        info!("Subscribing to {topic}");
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .subscribe(&topic.ident())?;
+        self.topics.insert(topic.hash(), topic.kind);
+
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, topic: Self::Topic) -> Result<()> {
+        info!("Unsubscribing from {topic}");
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .unsubscribe(&topic.ident());
+        self.topics.remove(&topic.hash());
 
         Ok(())
     }
 
     fn send(&mut self, topic: Self::Topic, data: Self::Data) -> Result<()> {
-        let topic = IdentTopic::new(topic);
+        let topic = topic.ident();
+        let span = data.span();
+        let _entered = span.enter();
         let data = data.into();
 
-        debug!("Publishing Gossipsub topic {topic:?} with data {data:?}",);
+        trace!(topic = %topic, bytes = data.len(), "Publishing gossipsub message");
         self.swarm.behaviour_mut().gossipsub.publish(topic, data)?;
 
         Ok(())
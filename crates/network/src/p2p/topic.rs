@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use libp2p::gossipsub::{IdentTopic, TopicHash};
+use std::fmt;
+use std::str::FromStr;
+
+/// Topic Kind
+///
+/// Category of game events sharing a gossipsub topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicKind {
+    Movement,
+    Chat,
+    Shares,
+    Presence,
+}
+
+impl TopicKind {
+    /// All the topic kinds
+    pub const ALL: [Self; 4] = [Self::Movement, Self::Chat, Self::Shares, Self::Presence];
+
+    /// Gets the name of the topic kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Movement => "movement",
+            Self::Chat => "chat",
+            Self::Shares => "shares",
+            Self::Presence => "presence",
+        }
+    }
+}
+
+impl FromStr for TopicKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| anyhow!("Unknown topic {s}"))
+    }
+}
+
+/// Topical
+///
+/// Used to pick the topic a message is published on
+pub trait Topical {
+    fn topic_kind(&self) -> TopicKind;
+}
+
+/// Game Topic
+///
+/// Gossipsub topic of a category, namespaced by network
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameTopic {
+    pub namespace: String,
+    pub kind: TopicKind,
+}
+
+impl GameTopic {
+    /// Creates a new topic within the namespace
+    pub fn new(namespace: impl Into<String>, kind: TopicKind) -> Self {
+        let namespace = namespace.into();
+        Self { namespace, kind }
+    }
+
+    /// Gets the gossipsub topic
+    pub fn ident(&self) -> IdentTopic {
+        IdentTopic::new(self.to_string())
+    }
+
+    /// Gets the gossipsub topic hash
+    pub fn hash(&self) -> TopicHash {
+        self.ident().hash()
+    }
+}
+
+impl fmt::Display for GameTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/fonketh/{}/{}", self.namespace, self.kind.name())
+    }
+}
@@ -0,0 +1,102 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Highest number of nonces mined per runner loop
+pub const MAX_WORKERS: usize = 1024;
+
+/// Reply channel of an admin command
+pub type Reply<T> = oneshot::Sender<Result<T, String>>;
+
+/// Admin Command
+///
+/// Used by operators to control the running world
+/// Answered by the runner between two loop iterations
+#[derive(Debug)]
+pub enum AdminCommand {
+    /// Gets the settings of the local miner
+    Miner(Reply<MinerStatus>),
+    /// Starts or stops the local miner
+    Mining(bool, Reply<MinerStatus>),
+    /// Sets the number of nonces mined per runner loop
+    Workers(usize, Reply<MinerStatus>),
+    /// Submits a claim of a full batch of pending shares right away
+    ///
+    /// The contract only takes batches of ten, partial batches are rejected
+    Claim(Reply<usize>),
+    /// Lists the connected peers
+    Peers(Reply<Vec<PeerInfo>>),
+    /// Bans a peer and disconnects it, for the default ban duration when unset
+    Ban(String, Option<Duration>, Reply<()>),
+    /// Dials a multiaddr
+    Dial(String, Reply<()>),
+}
+
+impl AdminCommand {
+    /// Gets the name of the command, without its arguments
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Miner(_) => "miner",
+            Self::Mining(..) => "mining",
+            Self::Workers(..) => "workers",
+            Self::Claim(_) => "claim",
+            Self::Peers(_) => "peers",
+            Self::Ban(..) => "ban",
+            Self::Dial(..) => "dial",
+        }
+    }
+}
+
+/// Miner Status
+///
+/// Runtime settings of the local miner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MinerStatus {
+    /// Whether the miner leases and mines nonce ranges
+    pub enabled: bool,
+    /// Number of nonces mined per runner loop
+    pub workers: usize,
+    /// Mined shares waiting for a claim batch
+    pub pending: usize,
+}
+
+/// Peer Info
+///
+/// Connected peer and its reputation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PeerInfo {
+    pub peer_id: String,
+    /// Reputation score of the peer
+    pub score: i32,
+}
+
+/// Log Filter
+///
+/// Hook replacing the log filter directives at runtime
+/// Returns an error for invalid directives
+#[derive(Clone)]
+pub struct LogFilter(Arc<Reload>);
+
+/// Replaces the log filter directives
+type Reload = dyn Fn(&str) -> Result<(), String> + Send + Sync;
+
+impl LogFilter {
+    /// Creates a new log filter hook
+    pub fn new(reload: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(reload))
+    }
+
+    /// Replaces the log filter directives
+    pub fn reload(&self, directives: &str) -> Result<(), String> {
+        (self.0)(directives)
+    }
+}
+
+impl Debug for LogFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LogFilter")
+    }
+}
@@ -1,9 +1,53 @@
+use crate::message::{ChatText, SealedMessage};
 use serde::{Deserialize, Serialize};
+use tracing::Span;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum GameEvent<F, P> {
+pub enum GameEvent<F, P, W> {
     Quit,
     PlayerMovement(P),
     PlayerFound(F),
-    ChatMessage(String),
+    ChatMessage(ChatText),
+    Work(W),
+    /// Chat message to the players of a named channel
+    ChannelMessage(String, ChatText),
+    /// Whisper to a player address or ENS name
+    /// Sealed into a direct message before reaching the network
+    Whisper(String, String),
+    /// End-to-end encrypted message to a single player
+    DirectMessage(SealedMessage),
+}
+
+impl<F, P, W> GameEvent<F, P, W> {
+    /// Name of the event type, used in logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEvent::Quit => "quit",
+            GameEvent::PlayerMovement(_) => "player_movement",
+            GameEvent::PlayerFound(_) => "player_found",
+            GameEvent::ChatMessage(_) => "chat_message",
+            GameEvent::Work(_) => "work",
+            GameEvent::ChannelMessage(..) => "channel_message",
+            GameEvent::Whisper(..) => "whisper",
+            GameEvent::DirectMessage(_) => "direct_message",
+        }
+    }
+}
+
+/// Traced
+///
+/// Used to hand a local event over to the world
+/// Carries the span it was captured in, to follow it across threads
+#[derive(Debug)]
+pub struct Traced<T> {
+    pub event: T,
+    pub span: Span,
+}
+
+impl<T> Traced<T> {
+    /// Wraps an event captured in the current span
+    pub fn new(event: T) -> Self {
+        let span = Span::current();
+        Self { event, span }
+    }
 }
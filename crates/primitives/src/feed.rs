@@ -0,0 +1,201 @@
+use crate::message::{ChatMessage, unix_millis};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Events kept to resume a feed
+pub const FEED_BACKLOG: usize = 1024;
+/// Events buffered per live subscriber
+const FEED_CAPACITY: usize = 256;
+
+/// Feed Topic
+///
+/// Category of the world events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedTopic {
+    Players,
+    Chat,
+    Shares,
+    Claims,
+}
+
+impl FeedTopic {
+    /// All the feed topics
+    pub const ALL: [FeedTopic; 4] = [
+        FeedTopic::Players,
+        FeedTopic::Chat,
+        FeedTopic::Shares,
+        FeedTopic::Claims,
+    ];
+
+    /// Name of the topic
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeedTopic::Players => "players",
+            FeedTopic::Chat => "chat",
+            FeedTopic::Shares => "shares",
+            FeedTopic::Claims => "claims",
+        }
+    }
+}
+
+impl Display for FeedTopic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FeedTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|topic| topic.name() == s)
+            .ok_or_else(|| format!("Unknown feed topic {s}"))
+    }
+}
+
+/// Claim Status
+///
+/// Progress of a claim transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStatus {
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+/// World Event
+///
+/// Change applied to the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum WorldEvent {
+    PlayerJoined {
+        player: String,
+        x: f64,
+        y: f64,
+    },
+    PlayerMoved {
+        player: String,
+        x: f64,
+        y: f64,
+    },
+    PlayerQuit {
+        player: String,
+    },
+    Chat {
+        message: ChatMessage,
+    },
+    Share {
+        player: String,
+        miner: String,
+        nonce: String,
+        zone: String,
+        block: bool,
+    },
+    Claim {
+        status: ClaimStatus,
+        count: usize,
+        transaction: Option<String>,
+    },
+}
+
+impl WorldEvent {
+    /// Topic of the event
+    pub fn topic(&self) -> FeedTopic {
+        match self {
+            WorldEvent::PlayerJoined { .. }
+            | WorldEvent::PlayerMoved { .. }
+            | WorldEvent::PlayerQuit { .. } => FeedTopic::Players,
+            WorldEvent::Chat { .. } => FeedTopic::Chat,
+            WorldEvent::Share { .. } => FeedTopic::Shares,
+            WorldEvent::Claim { .. } => FeedTopic::Claims,
+        }
+    }
+}
+
+/// Feed Event
+///
+/// World event tagged with its feed position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FeedEvent {
+    /// Increasing event id, used to resume the feed
+    pub id: u64,
+    /// Topic of the event
+    pub topic: FeedTopic,
+    /// The world event
+    pub event: WorldEvent,
+}
+
+/// Event Feed
+///
+/// Live stream of the world events with a bounded backlog
+/// Ids start from the creation time so they keep increasing across restarts
+#[derive(Debug)]
+pub struct EventFeed {
+    backlog: Mutex<(u64, VecDeque<FeedEvent>)>,
+    sender: broadcast::Sender<FeedEvent>,
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        let backlog = Mutex::new((unix_millis(), VecDeque::with_capacity(FEED_BACKLOG)));
+        let (sender, _) = broadcast::channel(FEED_CAPACITY);
+        Self { backlog, sender }
+    }
+}
+
+impl EventFeed {
+    /// Creates a new event feed
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Publishes an event to the subscribers
+    ///
+    /// Returns the id of the event
+    pub fn publish(&self, event: WorldEvent) -> u64 {
+        let mut backlog = self.backlog.lock().unwrap();
+        let (next, events) = &mut *backlog;
+        let event = FeedEvent {
+            id: *next,
+            topic: event.topic(),
+            event,
+        };
+        *next += 1;
+
+        if events.len() >= FEED_BACKLOG {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+
+        // No subscribers is not an error
+        let _ = self.sender.send(event.clone());
+        event.id
+    }
+
+    /// Subscribes to the feed
+    ///
+    /// Returns the backlog after the given id and the live events
+    pub fn subscribe(
+        &self,
+        since: Option<u64>,
+    ) -> (Vec<FeedEvent>, broadcast::Receiver<FeedEvent>) {
+        // Holding the backlog lock keeps events from slipping between both
+        let backlog = self.backlog.lock().unwrap();
+        let missed = match since {
+            Some(since) => backlog.1.iter().filter(|e| e.id > since).cloned().collect(),
+            None => Vec::new(),
+        };
+
+        (missed, self.sender.subscribe())
+    }
+}
@@ -0,0 +1,139 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Check Status
+///
+/// Used to grade a subsystem of the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Working as expected
+    Up,
+    /// Working with a reduced service
+    Degraded,
+    /// Not working
+    Down,
+}
+
+/// Runner Health
+///
+/// Liveness of the core loop
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunnerHealth {
+    pub status: CheckStatus,
+    /// Milliseconds since the last loop iteration
+    pub last_tick: u64,
+}
+
+/// Rpc Health
+///
+/// Reachability of the chain RPC, probed periodically
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RpcHealth {
+    pub status: CheckStatus,
+    /// Last block number returned by the RPC
+    pub block: Option<u64>,
+    /// Milliseconds since the last successful request
+    pub last_seen: Option<u64>,
+    /// Error of the last request, absent once it succeeds again
+    pub error: Option<String>,
+}
+
+/// Network Health
+///
+/// Connectivity of the peer-to-peer network
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkHealth {
+    pub status: CheckStatus,
+    /// Number of connected peers
+    pub peers: usize,
+    /// Milliseconds since the last gossip message
+    pub last_gossip: Option<u64>,
+}
+
+/// Miner Health
+///
+/// Activity of the local miner
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MinerHealth {
+    pub status: CheckStatus,
+    /// Whether the node is built with mining
+    pub enabled: bool,
+    /// Whether a nonce range is being mined
+    pub running: bool,
+}
+
+/// Claims Health
+///
+/// Backlog of the shares waiting to be claimed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClaimsHealth {
+    pub status: CheckStatus,
+    /// Mined shares waiting for a claim batch
+    pub pending: usize,
+    /// Claim transactions waiting for confirmation
+    pub in_flight: usize,
+}
+
+/// World Health
+///
+/// Snapshot of the subsystems status
+/// The runner and the RPC are critical, the world is not ready without them
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorldHealth {
+    /// Worst status of the subsystems
+    pub status: CheckStatus,
+    /// Milliseconds since the world started
+    pub uptime: u64,
+    pub runner: RunnerHealth,
+    pub rpc: RpcHealth,
+    pub network: NetworkHealth,
+    pub miner: MinerHealth,
+    pub claims: ClaimsHealth,
+}
+
+impl WorldHealth {
+    /// Creates a snapshot, grading the world by its subsystems
+    pub fn new(
+        uptime: u64,
+        runner: RunnerHealth,
+        rpc: RpcHealth,
+        network: NetworkHealth,
+        miner: MinerHealth,
+        claims: ClaimsHealth,
+    ) -> Self {
+        let critical = [runner.status, rpc.status];
+        let optional = [network.status, miner.status, claims.status];
+        let status = if critical.contains(&CheckStatus::Down) {
+            CheckStatus::Down
+        } else if critical
+            .iter()
+            .chain(&optional)
+            .all(|s| *s == CheckStatus::Up)
+        {
+            CheckStatus::Up
+        } else {
+            CheckStatus::Degraded
+        };
+
+        Self {
+            status,
+            uptime,
+            runner,
+            rpc,
+            network,
+            miner,
+            claims,
+        }
+    }
+
+    /// Checks that the core loop is running
+    pub fn is_alive(&self) -> bool {
+        self.runner.status != CheckStatus::Down
+    }
+
+    /// Checks that the critical subsystems are up
+    pub fn is_ready(&self) -> bool {
+        self.status != CheckStatus::Down
+    }
+}
@@ -1,11 +1,17 @@
+pub mod admin;
 pub mod events;
+pub mod feed;
+pub mod health;
+pub mod map;
 pub mod message;
+pub mod reputation;
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
 
 /// Identifier Trait
 ///
@@ -19,19 +25,31 @@ pub trait Identifier {
 /// Exit status
 ///
 /// Used to signal that the world should exit
+/// Subsystems can either poll it or wait for it
 #[derive(Debug, Default, Serialize, Deserialize)]
-pub struct ExitStatus(AtomicBool);
+pub struct ExitStatus(AtomicBool, #[serde(skip)] Notify);
 
 impl ExitStatus {
     /// Set the exit status to true
     pub fn exit(&self) {
         self.0.store(true, Ordering::Relaxed);
+        self.1.notify_waiters();
     }
 
     /// Check if the exit status is true
     pub fn is_exit(&self) -> bool {
         self.0.load(Ordering::Relaxed)
     }
+
+    /// Waits until the exit status is set
+    pub async fn wait(&self) {
+        let notified = self.1.notified();
+        if self.is_exit() {
+            return;
+        }
+
+        notified.await;
+    }
 }
 
 /// World state
@@ -39,8 +57,9 @@ impl ExitStatus {
 /// Used to store the state of the world
 pub trait WorldState: Identifier {
     type Player: Player;
-    type Message: Display;
-    type MiningBatch: Serialize;
+    type Message: Display + Scoped + Record;
+    type MiningBatch: Serialize + Record;
+    type Map: WorldMap;
 
     /// Gets the exit status of the world
     fn exit_status(&self) -> Arc<ExitStatus>;
@@ -50,8 +69,56 @@ pub trait WorldState: Identifier {
     fn get_mining_rewards_count(&self) -> u32;
     /// Gets Current Mining Batch
     fn get_mining_batch(&self) -> HashSet<Self::MiningBatch>;
-    /// Gets the current chat messages
+    /// Gets the current chat messages of unmuted players
     fn get_chat_messages(&self) -> Vec<Self::Message>;
+    /// Gets the chat messages matching a channel filter
+    fn get_channel_messages(&self, channel: &str) -> Vec<Self::Message> {
+        self.get_chat_messages()
+            .into_iter()
+            .filter(|m| m.scope().matches(channel))
+            .collect()
+    }
+    /// Mutes or unmutes a player by address or ENS name
+    ///
+    /// Returns false if the player is unknown
+    fn mute(&self, name: &str, muted: bool) -> bool;
+    /// Blocks or unblocks a player by address or ENS name
+    ///
+    /// Returns false if the player is unknown
+    fn block(&self, name: &str, blocked: bool) -> bool;
+    /// Gets the world map
+    fn get_map(&self) -> Arc<Self::Map>;
+    /// Gets the status of the mining zones
+    fn get_zones(&self) -> Vec<map::ZoneStatus>;
+    /// Gets the accumulated share work of a miner
+    fn get_miner_contribution(&self, miner: &str) -> u64;
+    /// Gets the shares still remembered for a miner, oldest first
+    fn get_miner_shares(&self, miner: &str) -> Vec<map::MinedShare>;
+    /// Gets the active bans
+    fn get_bans(&self) -> Vec<reputation::BanEntry>;
+    /// Gets the live feed of the world events
+    fn feed(&self) -> Arc<feed::EventFeed>;
+    /// Records the frame rate of the interface
+    fn record_fps(&self, fps: f64);
+    /// Gets the health of the world subsystems
+    fn health(&self) -> health::WorldHealth;
+}
+
+/// Scoped
+///
+/// Used to get the audience of a chat message
+pub trait Scoped {
+    fn scope(&self) -> &message::ChatScope;
+}
+
+/// Record
+///
+/// Used to page and filter the records served by the API
+pub trait Record {
+    /// Address of the player behind the record
+    fn owner(&self) -> String;
+    /// Creation time in unix milliseconds
+    fn timestamp(&self) -> u64;
 }
 
 /// Player
@@ -73,3 +140,13 @@ pub trait Position {
     fn x(&self) -> f64;
     fn y(&self) -> f64;
 }
+
+/// World Map
+///
+/// Used to describe the tiles of the world
+pub trait WorldMap {
+    /// Gets the tile size in pixels
+    fn tile_size(&self) -> (u32, u32);
+    /// Gets all renderable tiles of the map
+    fn tiles(&self) -> Vec<map::MapTile>;
+}
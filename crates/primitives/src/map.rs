@@ -0,0 +1,60 @@
+use crate::Record;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Map Tile
+///
+/// Used to describe a single renderable tile of the world map
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapTile {
+    /// The tile position in world units
+    pub position: (i32, i32),
+    /// The layer depth, starting from the bottom
+    pub layer: usize,
+    /// The tileset image path
+    pub tileset: String,
+    /// The tile index within the tileset
+    pub index: u32,
+    /// The tileset grid (columns, rows)
+    pub grid: (u32, u32),
+}
+
+/// Zone Status
+///
+/// Used to describe the occupancy of a mining zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZoneStatus {
+    /// The zone name
+    pub name: String,
+    /// The number of players inside the zone
+    pub players: usize,
+    /// The share of mining work found in the zone
+    pub share: f64,
+    /// Whether the local player is inside the zone
+    pub local: bool,
+}
+
+/// Mined Share
+///
+/// Used to list a share accounted for a miner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MinedShare {
+    /// The miner address
+    pub miner: String,
+    /// The share nonce
+    pub nonce: String,
+    /// Whether the share is also a block
+    pub block: bool,
+    /// The time the share was accounted in unix milliseconds
+    pub timestamp: u64,
+}
+
+impl Record for MinedShare {
+    fn owner(&self) -> String {
+        self.miner.clone()
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
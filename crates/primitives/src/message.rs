@@ -1,58 +1,144 @@
-use serde::ser::{Error, SerializeStruct};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
-use std::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum length of a chat message in bytes
+pub const MAX_MESSAGE_LENGTH: usize = 280;
+/// Maximum length of a channel name in bytes
+pub const MAX_CHANNEL_LENGTH: usize = 32;
+/// Channel filter matching global messages
+pub const GLOBAL_CHANNEL: &str = "global";
+/// Channel filter matching direct messages
+pub const DIRECT_CHANNEL: &str = "direct";
+
+/// Chat Scope
+///
+/// Audience of a chat message
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "name")]
+pub enum ChatScope {
+    /// Every player
+    Global,
+    /// Players of a named channel
+    Channel(String),
+    /// A single player, named after the other side of the conversation
+    Direct(String),
+}
+
+impl ChatScope {
+    /// Checks if the scope matches a channel filter
+    pub fn matches(&self, channel: &str) -> bool {
+        match self {
+            ChatScope::Global => channel == GLOBAL_CHANNEL,
+            ChatScope::Channel(name) => name == channel,
+            ChatScope::Direct(_) => channel == DIRECT_CHANNEL,
+        }
+    }
+}
+
+/// Chat Text
+///
+/// Chat payload written and signed by the sender
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatText {
+    /// The message content
+    pub text: String,
+    /// The unix time in milliseconds the sender wrote the message
+    pub timestamp: u64,
+}
+
+impl ChatText {
+    /// Creates a new chat text written now
+    pub fn new(text: String) -> Self {
+        let timestamp = unix_millis();
+        Self { text, timestamp }
+    }
+}
+
+/// Sealed Message
+///
+/// End-to-end encrypted payload of a direct message
+/// Only the recipient can open it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedMessage {
+    /// The recipient's identifier
+    pub recipient: Vec<u8>,
+    /// The sender's ephemeral public key
+    pub ephemeral: Vec<u8>,
+    /// The cipher nonce
+    pub nonce: Vec<u8>,
+    /// The encrypted message
+    pub ciphertext: Vec<u8>,
+}
 
 /// Chat Message
 ///
 /// Used to represent a chat message
 /// Sent by a player to the world
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct ChatMessage {
-    /// The player's identifier
-    pub identifier: String,
+    /// The unique message id
+    pub id: String,
+    /// The sender's address
+    pub sender: String,
+    /// The sender's display name
+    pub name: String,
     /// The message content
     pub message: String,
-    /// The timestamp of the message
-    pub timestamp: Instant,
-}
-
-impl serde::Serialize for ChatMessage {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let epoch = std::time::UNIX_EPOCH.elapsed().map_err(Error::custom)?;
-        let timestamp = epoch - self.timestamp.elapsed();
-
-        let mut s = serializer.serialize_struct("ChatMessage", 3)?;
-        s.serialize_field("identifier", &self.identifier)?;
-        s.serialize_field("message", &self.message)?;
-        s.serialize_field("timestamp", &timestamp.as_secs())?;
-        s.end()
-    }
+    /// The unix time in milliseconds the sender wrote the message
+    pub timestamp: u64,
+    /// The audience of the message
+    pub scope: ChatScope,
 }
 
 impl ChatMessage {
     /// Creates a new chat message
-    pub fn new(identifier: String, message: String) -> Self {
-        let timestamp = Instant::now();
-
+    pub fn new(id: String, sender: String, name: String, text: ChatText, scope: ChatScope) -> Self {
         Self {
-            identifier,
-            message,
-            timestamp,
+            id,
+            sender,
+            name,
+            message: text.text,
+            timestamp: text.timestamp,
+            scope,
         }
     }
 }
 
+impl crate::Scoped for ChatMessage {
+    fn scope(&self) -> &ChatScope {
+        &self.scope
+    }
+}
+
+impl crate::Record for ChatMessage {
+    fn owner(&self) -> String {
+        self.sender.clone()
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
 impl Display for ChatMessage {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(
-            f,
-            "{}: {} | {}s ago",
-            self.identifier,
-            self.message,
-            self.timestamp.elapsed().as_secs()
-        )
+        match &self.scope {
+            ChatScope::Global => {}
+            ChatScope::Channel(name) => write!(f, "[#{name}] ")?,
+            ChatScope::Direct(name) => write!(f, "[@{name}] ")?,
+        }
+
+        let elapsed = unix_millis().saturating_sub(self.timestamp) / 1000;
+        write!(f, "{}: {} | {elapsed}s ago", self.name, self.message)
     }
 }
+
+/// Gets the current unix time in milliseconds
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
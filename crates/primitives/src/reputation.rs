@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Ban Kind
+///
+/// Used to tell what a ban applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum BanKind {
+    Peer,
+    Address,
+}
+
+/// Ban Entry
+///
+/// Used to represent an active ban
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BanEntry {
+    /// The banned peer id or address
+    pub identifier: String,
+    /// What the identifier refers to
+    pub kind: BanKind,
+    /// The reputation score that led to the ban
+    pub score: i32,
+    /// The unix time the ban ends
+    pub banned_until: u64,
+}